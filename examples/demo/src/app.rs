@@ -43,9 +43,9 @@ fn HomePage() -> impl IntoView {
 
     // leptos_hotkey specific logic
     fn go_to_link(key: &'static str, link: String, scope: &'static str) {
-        use_hotkeys!((*key, scope) => move |_| {
+        use_hotkeys!((key, scope) => move |_| {
             window().location().set_href(&link).expect("Failed to navigate");
-        })
+        });
     }
 
     let (count, set_count) = create_signal(0);
@@ -54,8 +54,8 @@ fn HomePage() -> impl IntoView {
 
     // global hotkeys
     use_hotkeys!(("s") => move |_| {
-        toggle_scope("scope_a".to_string());
-        toggle_scope("scope_b".to_string());
+        toggle_scope("scope_a".into());
+        toggle_scope("scope_b".into());
 
         if current_scope.get() == "scope_a" {
             current_scope.set("scope_b")
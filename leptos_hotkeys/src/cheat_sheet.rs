@@ -0,0 +1,223 @@
+use crate::scope::{is_scope_active, Scope};
+use crate::{
+    use_hotkeys_context, use_hotkeys_scoped_with_options, HotkeyOptions, RegisteredHotkey,
+};
+use leptos::*;
+use std::collections::HashSet;
+
+/// Modal listing the bindings of the active scopes, grouped by scope and by
+/// [`HotkeyOptions::group`].
+///
+/// It is unstyled: every element has a `hotkeys-cheat-sheet*` class to theme
+/// it, like `hotkeys-cheat-sheet__hotkey` for the `<kbd>` of each hotkey.
+#[component]
+pub fn HotkeysCheatSheet(
+    /// Key combination toggling the cheat sheet.
+    #[prop(into, default = "shift+key:?".to_string())]
+    toggle: String,
+    /// Scopes the toggle binding is active in.
+    #[prop(default = vec![Scope::GLOBAL])]
+    scopes: Vec<Scope>,
+    /// Whether the cheat sheet is open, to open or close it from elsewhere.
+    #[prop(optional)]
+    open: Option<RwSignal<bool>>,
+) -> impl IntoView {
+    let hotkeys_context = use_hotkeys_context();
+    let open = open.unwrap_or_else(|| RwSignal::new(false));
+
+    use_hotkeys_scoped_with_options(
+        toggle,
+        Callback::new(move |_| open.update(|open| *open = !*open)),
+        scopes,
+        HotkeyOptions::new().description("Show keyboard shortcuts"),
+    );
+
+    let registry = hotkeys_context.registry();
+    let sections = move || {
+        hotkeys_context
+            .active_scopes
+            .with(|active_scopes| sections(&registry.get(), active_scopes))
+    };
+
+    let binding_view = move |binding: RegisteredHotkey| {
+        let platform = hotkeys_context.platform.get();
+        let hotkeys = binding
+            .hotkeys
+            .iter()
+            .map(|hotkey| {
+                view! {
+                    <kbd class="hotkeys-cheat-sheet__hotkey">
+                        {hotkey.label(platform)}
+                    </kbd>
+                }
+            })
+            .collect_view();
+
+        view! {
+            <li class="hotkeys-cheat-sheet__binding">
+                <span class="hotkeys-cheat-sheet__hotkeys">{hotkeys}</span>
+                <span class="hotkeys-cheat-sheet__description">{binding.description}</span>
+            </li>
+        }
+    };
+
+    let section_view = move |section: Section| {
+        let groups = section
+            .groups
+            .into_iter()
+            .map(|group| {
+                view! {
+                    <div class="hotkeys-cheat-sheet__group">
+                        {group
+                            .name
+                            .map(|name| view! { <h3 class="hotkeys-cheat-sheet__group-name">{name}</h3> })}
+                        <ul class="hotkeys-cheat-sheet__bindings">
+                            {group.bindings.into_iter().map(binding_view).collect_view()}
+                        </ul>
+                    </div>
+                }
+            })
+            .collect_view();
+
+        view! {
+            <section class="hotkeys-cheat-sheet__scope">
+                <h2 class="hotkeys-cheat-sheet__scope-name">{scope_label(section.scope)}</h2>
+                {groups}
+            </section>
+        }
+    };
+
+    view! {
+        <Show when=move || open.get()>
+            <div class="hotkeys-cheat-sheet" role="dialog" aria-label="Keyboard shortcuts">
+                <button class="hotkeys-cheat-sheet__close" on:click=move |_| open.set(false)>
+                    "Close"
+                </button>
+                {move || sections().into_iter().map(section_view).collect_view()}
+            </div>
+        </Show>
+    }
+}
+
+/// Bindings of an active scope.
+#[derive(Debug, Clone, PartialEq)]
+struct Section {
+    scope: Scope,
+    groups: Vec<Group>,
+}
+
+/// Bindings of a scope sharing the same [`HotkeyOptions::group`].
+#[derive(Debug, Clone, PartialEq)]
+struct Group {
+    name: Option<String>,
+    bindings: Vec<RegisteredHotkey>,
+}
+
+/// Active bindings by active scope, the global `*` scope first and the others
+/// sorted by name. A binding of `editor` or `editor.*` is listed under that
+/// scope rather than under the active `editor.vim`, and bindings gated only by
+/// a when clause are listed with the global ones. Groups keep the order they
+/// are first registered in.
+fn sections(registry: &[RegisteredHotkey], active_scopes: &HashSet<Scope>) -> Vec<Section> {
+    let mut scopes = registry
+        .iter()
+        .flat_map(|binding| &binding.scopes)
+        .chain(active_scopes.get("*"))
+        .filter(|scope| is_scope_active(scope, active_scopes))
+        .collect::<Vec<_>>();
+    scopes.sort_by_key(|scope| (**scope != Scope::GLOBAL, *scope));
+    scopes.dedup();
+
+    scopes
+        .into_iter()
+        .filter_map(|scope| {
+            let mut groups: Vec<Group> = Vec::new();
+            let bindings = registry
+                .iter()
+                .filter(|binding| binding.active)
+                .filter(|binding| match binding.scopes.is_empty() {
+                    true => *scope == Scope::GLOBAL,
+                    false => binding.scopes.contains(scope),
+                });
+            for binding in bindings {
+                match groups.iter_mut().find(|group| group.name == binding.group) {
+                    Some(group) => group.bindings.push(binding.clone()),
+                    None => groups.push(Group {
+                        name: binding.group.clone(),
+                        bindings: vec![binding.clone()],
+                    }),
+                }
+            }
+
+            (!groups.is_empty()).then(|| Section {
+                scope: scope.clone(),
+                groups,
+            })
+        })
+        .collect()
+}
+
+fn scope_label(scope: Scope) -> String {
+    match scope.as_str() {
+        "*" => "Global".to_string(),
+        _ => scope.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Hotkey;
+
+    fn registered(hotkey: &str, scopes: &[&str], group: Option<&str>) -> RegisteredHotkey {
+        RegisteredHotkey {
+            id: 0,
+            hotkeys: vec![Hotkey::new(hotkey)],
+            scopes: scopes.iter().map(|scope| Scope::from(*scope)).collect(),
+            when: None,
+            description: None,
+            group: group.map(str::to_string),
+            action: None,
+            active: true,
+            focus_trapped: false,
+        }
+    }
+
+    #[test]
+    fn sections_by_scope_and_group() {
+        let mut inactive = registered("ctrl+x", &["modal"], None);
+        inactive.active = false;
+        let registry = vec![
+            registered("g i", &["*"], Some("Navigation")),
+            registered("shift+key:?", &["*"], None),
+            registered("ctrl+s", &["editor"], Some("File")),
+            registered("ctrl+i", &["editor.vim.*"], None),
+            registered("g s", &["*"], Some("Navigation")),
+            registered("ctrl+f", &[], None),
+            inactive,
+        ];
+        let active_scopes = HashSet::from([Scope::from("editor.vim.insert"), Scope::GLOBAL]);
+
+        let sections = sections(&registry, &active_scopes);
+        let summary = sections
+            .iter()
+            .map(|section| {
+                let groups = section
+                    .groups
+                    .iter()
+                    .map(|group| (group.name.as_deref(), group.bindings.len()))
+                    .collect::<Vec<_>>();
+                (section.scope.as_str(), groups)
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            summary,
+            vec![
+                ("*", vec![(Some("Navigation"), 2), (None, 2)]),
+                ("editor", vec![(Some("File"), 1)]),
+                ("editor.vim.*", vec![(None, 1)]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,255 @@
+use crate::{
+    use_hotkeys_context, use_hotkeys_scoped_with_options, HotkeyOptions, RegisteredHotkey, Scope,
+};
+use leptos::*;
+use std::collections::HashSet;
+
+/// Modal listing the bindings of the active scopes, to filter them by typing
+/// and run one with the keyboard or the mouse.
+///
+/// It lists the bindings that were active when it was opened.
+///
+/// While open, it pushes its own `scope` as an exclusive scope with
+/// [`HotkeysContext::push_scope`](crate::HotkeysContext::push_scope), so typing
+/// in it doesn't trigger other hotkeys, and pops it when closed. It is
+/// unstyled: every element has a `command-palette*` class to theme it.
+#[component]
+pub fn CommandPalette(
+    /// Key combination toggling the palette.
+    #[prop(into, default = "mod+shift+key:p".to_string())]
+    toggle: String,
+    /// Scope active while the palette is open.
+    #[prop(into, default = Scope::from_static("command_palette"))]
+    scope: Scope,
+    /// Whether the palette is open, to open or close it from elsewhere.
+    #[prop(optional)]
+    open: Option<RwSignal<bool>>,
+) -> impl IntoView {
+    let hotkeys_context = use_hotkeys_context();
+    let open = open.unwrap_or_else(|| RwSignal::new(false));
+    let query = RwSignal::new(String::new());
+    let selected = RwSignal::new(0);
+    let available: RwSignal<HashSet<usize>> = RwSignal::new(HashSet::new());
+    let input_ref = create_node_ref::<html::Input>();
+
+    use_hotkeys_scoped_with_options(
+        toggle,
+        Callback::new(move |_| open.update(|open| *open = !*open)),
+        vec![Scope::GLOBAL, scope.clone()],
+        HotkeyOptions::new().description("Open the command palette"),
+    );
+
+    let registry = hotkeys_context.registry();
+    let palette_scope = scope.clone();
+    create_effect(move |was_open: Option<bool>| {
+        let is_open = open.get();
+        if is_open && was_open != Some(true) {
+            available.set(registry.with_untracked(|registry| {
+                registry
+                    .iter()
+                    .filter(|binding| binding.active)
+                    .map(|binding| binding.id)
+                    .collect()
+            }));
+            hotkeys_context.push_scope(scope.clone(), true);
+            query.set(String::new());
+            selected.set(0);
+        } else if !is_open && was_open == Some(true) {
+            hotkeys_context.pop_scope_named(scope.clone());
+        }
+        is_open
+    });
+
+    let actions = create_memo(move |_| {
+        available.with(|available| {
+            query.with(|query| actions(&registry.get(), available, &palette_scope, query))
+        })
+    });
+
+    let run = move |id: usize| {
+        open.set(false);
+        hotkeys_context.trigger(id);
+    };
+
+    let on_keydown = move |event: ev::KeyboardEvent| {
+        let count = actions.with(Vec::len);
+        match event.key().as_str() {
+            "ArrowDown" => selected.update(|i| *i = (*i + 1).min(count.saturating_sub(1))),
+            "ArrowUp" => selected.update(|i| *i = i.saturating_sub(1)),
+            "Enter" => {
+                if let Some(action) = actions.with(|actions| actions.get(selected.get()).cloned()) {
+                    run(action.id);
+                }
+            }
+            "Escape" => open.set(false),
+            _ => return,
+        }
+        event.prevent_default();
+    };
+
+    input_ref.on_load(move |input| {
+        let _ = input.focus();
+    });
+
+    let action_view = move |(index, action): (usize, RegisteredHotkey)| {
+        let platform = hotkeys_context.platform.get();
+        let label = action
+            .description
+            .clone()
+            .unwrap_or_else(|| action.hotkeys[0].label(platform));
+        let hotkeys = action
+            .hotkeys
+            .iter()
+            .map(|hotkey| {
+                view! {
+                    <kbd class="command-palette__hotkey">{hotkey.label(platform)}</kbd>
+                }
+            })
+            .collect_view();
+
+        view! {
+            <li
+                class="command-palette__action"
+                class=("command-palette__action--selected", move || selected.get() == index)
+                role="option"
+                aria-selected=move || (selected.get() == index).to_string()
+                on:click=move |_| run(action.id)
+            >
+                <span class="command-palette__description">{label}</span>
+                <span class="command-palette__hotkeys">{hotkeys}</span>
+            </li>
+        }
+    };
+
+    view! {
+        <Show when=move || open.get()>
+            <div class="command-palette" role="dialog" aria-label="Command palette">
+                <input
+                    class="command-palette__input"
+                    type="text"
+                    placeholder="Type a command"
+                    node_ref=input_ref
+                    prop:value=move || query.get()
+                    on:input=move |event| {
+                        query.set(event_target_value(&event));
+                        selected.set(0);
+                    }
+                    on:keydown=on_keydown
+                />
+                <ul class="command-palette__actions" role="listbox">
+                    {move || actions.get().into_iter().enumerate().map(action_view).collect_view()}
+                </ul>
+            </div>
+        </Show>
+    }
+}
+
+/// Bindings of `available`, those active when the palette was opened,
+/// matching `query`, best matches first.
+///
+/// Focus trapped bindings are left out, as they only make sense in their own
+/// element, and so are the bindings of the palette in `palette_scope`.
+fn actions(
+    registry: &[RegisteredHotkey],
+    available: &HashSet<usize>,
+    palette_scope: &Scope,
+    query: &str,
+) -> Vec<RegisteredHotkey> {
+    let mut actions = registry
+        .iter()
+        .filter(|binding| !binding.focus_trapped)
+        .filter(|binding| !binding.scopes.iter().any(|scope| scope == palette_scope))
+        .filter(|binding| available.contains(&binding.id))
+        .filter_map(|binding| {
+            let hotkeys = binding.hotkeys.iter().map(ToString::to_string);
+            binding
+                .description
+                .iter()
+                .cloned()
+                .chain(hotkeys)
+                .filter_map(|text| fuzzy_score(query, &text))
+                .min()
+                .map(|score| (score, binding))
+        })
+        .collect::<Vec<_>>();
+
+    // stable, so bindings with the same score keep their registration order
+    actions.sort_by_key(|(score, _)| *score);
+    actions
+        .into_iter()
+        .map(|(_, binding)| binding.clone())
+        .collect()
+}
+
+/// Whether the characters of `query` appear in order in `text`, ignoring case,
+/// with the number of characters skipped between them. Lower is better.
+fn fuzzy_score(query: &str, text: &str) -> Option<usize> {
+    let mut text = text.chars().flat_map(char::to_lowercase);
+    let mut score = 0;
+    let mut started = false;
+
+    for q in query.chars().flat_map(char::to_lowercase) {
+        if q.is_whitespace() {
+            continue;
+        }
+        loop {
+            let c = text.next()?;
+            if c == q {
+                started = true;
+                break;
+            }
+            if started {
+                score += 1;
+            }
+        }
+    }
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Hotkey;
+
+    #[test]
+    fn fuzzy_filter() {
+        assert_eq!(fuzzy_score("", "Open file"), Some(0));
+        assert_eq!(fuzzy_score("open", "Open file"), Some(0));
+        assert_eq!(fuzzy_score("of", "Open file"), Some(4));
+        assert_eq!(fuzzy_score("fo", "Open file"), None);
+
+        let action = |id, description: &str, scopes: &[&str]| RegisteredHotkey {
+            id,
+            hotkeys: vec![Hotkey::new("ctrl+o")],
+            scopes: scopes.iter().map(|scope| Scope::from(*scope)).collect(),
+            when: None,
+            description: Some(description.to_string()),
+            group: None,
+            action: None,
+            active: true,
+            focus_trapped: false,
+        };
+        let registry = vec![
+            action(0, "Save file", &["*"]),
+            action(1, "Open file", &["*"]),
+            action(2, "Open settings", &["settings"]),
+            action(3, "Open the command palette", &["*", "command_palette"]),
+        ];
+
+        let ids = |query| {
+            actions(
+                &registry,
+                &HashSet::from([0, 1, 3]),
+                &Scope::from("command_palette"),
+                query,
+            )
+            .iter()
+            .map(|action| action.id)
+            .collect::<Vec<_>>()
+        };
+        assert_eq!(ids(""), vec![0, 1]);
+        assert_eq!(ids("file"), vec![0, 1]);
+        assert_eq!(ids("open"), vec![1]);
+        assert_eq!(ids("ctrl"), vec![0, 1]);
+    }
+}
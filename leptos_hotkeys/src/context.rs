@@ -1,6 +1,14 @@
+use crate::hotkey::{is_key_event, parse_key_combination};
+use crate::registry::{find_conflicts, Binding, Conditions};
+use crate::{
+    Hotkey, HotkeyConflict, HotkeyEvent, HotkeyParseError, HotkeyScope, KeyMode, Keymap,
+    KeymapEntry, KeymapOverrides, KeymapStorage, Platform, RegisteredHotkey, Scope, WhenClause,
+};
 use leptos::html::ElementDescriptor;
 use leptos::*;
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::rc::Rc;
+use std::time::Duration;
 #[cfg(not(feature = "ssr"))]
 use wasm_bindgen::JsCast;
 
@@ -15,22 +23,390 @@ pub struct HotkeysContext {
     #[cfg(not(feature = "ssr"))]
     pub set_ref_target: Callback<Option<web_sys::EventTarget>>,
 
-    pub active_scopes: RwSignal<HashSet<String>>,
-    pub enable_scope: Callback<String>,
-    pub disable_scope: Callback<String>,
-    pub toggle_scope: Callback<String>,
+    pub active_scopes: RwSignal<HashSet<Scope>>,
+    pub enable_scope: Callback<Scope>,
+    pub disable_scope: Callback<Scope>,
+    pub toggle_scope: Callback<Scope>,
+
+    /// Maximum delay between two chords of a sequence like `"ctrl+k ctrl+s"`,
+    /// for bindings that don't set their own.
+    pub sequence_timeout: RwSignal<Duration>,
+    /// Chords typed so far of the longest sequence in progress.
+    pub pending_sequence: Signal<Vec<Hotkey>>,
+    /// Platform the `mod` modifier is resolved for. Detected when the context
+    /// is provided, and can be overridden.
+    pub platform: RwSignal<Platform>,
+    /// How key names without a `key:` or `code:` prefix are compared with key
+    /// presses, for bindings that don't set their own. Defaults to
+    /// [`KeyMode::default`], and can be set on the context returned by
+    /// [`provide_hotkeys_context`].
+    pub key_mode: RwSignal<KeyMode>,
+
+    #[cfg_attr(feature = "ssr", allow(dead_code))]
+    pub(crate) sequence_progress: RwSignal<BTreeMap<usize, Vec<Hotkey>>>,
+    pub(crate) bindings: RwSignal<BTreeMap<usize, Binding>>,
+    /// Scopes pushed with `push_scope`, with what each push changed.
+    scope_stack: RwSignal<Vec<PushedScope>>,
+    /// Number of mounted `use_scope` calls by scope.
+    scope_users: StoredValue<HashMap<Scope, usize>>,
+    context_keys: RwSignal<HashMap<String, (usize, Signal<bool>)>>,
+    true_context_keys: Memo<HashSet<String>>,
+    conflicts: Signal<Vec<HotkeyConflict>>,
+    registry: Signal<Vec<RegisteredHotkey>>,
+    keymap: RwSignal<KeymapOverrides>,
+    keymap_storage: StoredValue<Option<Rc<dyn KeymapStorage>>>,
+    next_id: StoredValue<usize>,
+}
+
+pub(crate) const DEFAULT_SEQUENCE_TIMEOUT: Duration = Duration::from_millis(1000);
+
+/// A scope pushed with [`HotkeysContext::push_scope`], and what the push
+/// changed so [`HotkeysContext::pop_scope`] can undo it.
+#[derive(Debug, Clone)]
+struct PushedScope {
+    scope: Scope,
+    exclusive: bool,
+    /// Whether the scope wasn't already active.
+    inserted: bool,
+    /// Active scopes an exclusive push deactivated.
+    suppressed: HashSet<Scope>,
+}
+
+impl HotkeysContext {
+    /// Activates `scope` on top of the active scopes until [`pop_scope`](Self::pop_scope),
+    /// for a modal or a menu. An `exclusive` scope suppresses every other
+    /// scope, including the global `*` scope, so only its bindings fire.
+    pub fn push_scope(&self, scope: impl HotkeyScope, exclusive: bool) {
+        let scope = scope.into_scope();
+        if cfg!(feature = "debug") {
+            logging::log!("pushing scope {}", &scope);
+        }
+
+        let mut pushed = PushedScope {
+            scope: scope.clone(),
+            exclusive,
+            inserted: false,
+            suppressed: HashSet::new(),
+        };
+        self.active_scopes.update(|scopes| {
+            pushed.inserted = !scopes.remove(&scope);
+            if exclusive {
+                pushed.suppressed = std::mem::take(scopes);
+            }
+            scopes.insert(scope);
+        });
+        self.scope_stack.update(|stack| stack.push(pushed));
+    }
+
+    /// Removes the last scope pushed with [`push_scope`](Self::push_scope), and
+    /// undoes what its push changed: the scope is deactivated unless it was
+    /// already active, and the scopes an exclusive push suppressed come back.
+    /// Scopes enabled or disabled in the meantime keep their state. Returns
+    /// the scope, or `None` if none is pushed.
+    pub fn pop_scope(&self) -> Option<Scope> {
+        let scope = self
+            .scope_stack
+            .with_untracked(|stack| stack.last().map(|pushed| pushed.scope.clone()))?;
+        self.pop_scope_named(scope)
+    }
+
+    /// Removes the last push of `scope`, even if other scopes were pushed on
+    /// top of it since, for a component that pushed its own scope. What the
+    /// push changed is undone like [`pop_scope`](Self::pop_scope) does, except
+    /// that scopes it suppressed stay suppressed while an exclusive scope
+    /// above it is pushed. Returns the scope, or `None` if it isn't pushed.
+    pub fn pop_scope_named(&self, scope: impl HotkeyScope) -> Option<Scope> {
+        let scope = scope.into_scope();
+        let pushed = self
+            .scope_stack
+            .try_update(|stack| {
+                let index = stack.iter().rposition(|pushed| pushed.scope == scope)?;
+                let mut pushed = stack.remove(index);
+                let above = &mut stack[index..];
+                if pushed.inserted {
+                    for above in above.iter_mut() {
+                        above.suppressed.remove(&pushed.scope);
+                    }
+                }
+                if let Some(exclusive) = above.iter_mut().find(|above| above.exclusive) {
+                    exclusive
+                        .suppressed
+                        .extend(std::mem::take(&mut pushed.suppressed));
+                }
+                Some(pushed)
+            })
+            .flatten()?;
+        if cfg!(feature = "debug") {
+            logging::log!("popping scope {}", &pushed.scope);
+        }
+        self.active_scopes.update(|scopes| {
+            if pushed.inserted {
+                scopes.remove(&pushed.scope);
+            }
+            scopes.extend(pushed.suppressed);
+        });
+        Some(pushed.scope)
+    }
+
+    /// Enables `scope` for its first user, see [`use_scope`](crate::use_scope).
+    pub(crate) fn retain_scope(&self, scope: Scope) {
+        let mut first = false;
+        self.scope_users.update_value(|users| {
+            let count = users.entry(scope.clone()).or_default();
+            first = *count == 0;
+            *count += 1;
+        });
+        if first {
+            Callable::call(&self.enable_scope, scope);
+        }
+    }
+
+    /// Disables `scope` when its last user is cleaned up.
+    pub(crate) fn release_scope(&self, scope: Scope) {
+        let mut last = false;
+        self.scope_users.try_update_value(|users| {
+            if let Some(count) = users.get_mut(&scope) {
+                *count -= 1;
+                if *count == 0 {
+                    users.remove(&scope);
+                    last = true;
+                }
+            }
+        });
+        if last {
+            Callable::call(&self.disable_scope, scope);
+        }
+    }
+
+    /// Scopes pushed with [`push_scope`](Self::push_scope), the last one on top.
+    pub fn scope_stack(&self) -> Signal<Vec<Scope>> {
+        let scope_stack = self.scope_stack;
+        Signal::derive(move || {
+            scope_stack.with(|stack| stack.iter().map(|pushed| pushed.scope.clone()).collect())
+        })
+    }
+
+    /// Ambiguous bindings: active bindings triggered by the same hotkey without
+    /// a priority to pick one of them. All of them fire.
+    pub fn conflicts(&self) -> Signal<Vec<HotkeyConflict>> {
+        self.conflicts
+    }
+
+    /// Every binding registered in this context, in registration order.
+    pub fn registry(&self) -> Signal<Vec<RegisteredHotkey>> {
+        self.registry
+    }
+
+    /// Runs the callback of the binding `id` of the [`registry`](Self::registry),
+    /// as if its hotkey was pressed.
+    ///
+    /// The [`HotkeyEvent`] it gets has no keyboard event, and the first of the
+    /// hotkeys of the binding in display order.
+    pub fn trigger(&self, id: usize) {
+        let triggered = self.bindings.with_untracked(|bindings| {
+            let binding = bindings.get(&id)?;
+            let hotkey = binding
+                .hotkeys
+                .iter()
+                .min_by_key(|hotkey| hotkey.to_string())?;
+            Some((binding.on_triggered, hotkey.clone()))
+        });
+        if let Some((on_triggered, hotkey)) = triggered {
+            Callable::call(&on_triggered, HotkeyEvent::new(hotkey, None));
+        }
+    }
+
+    /// Key combinations chosen by the user for named actions, which replace the
+    /// ones the actions were registered with.
+    ///
+    /// Actions are named with [`HotkeyOptions::action`](crate::HotkeyOptions::action).
+    pub fn keymap(&self) -> Signal<KeymapOverrides> {
+        self.keymap.into()
+    }
+
+    /// Binds `action` to `key_combination` instead of the key combination it
+    /// was registered with, and saves the keymap.
+    pub fn rebind(&self, action: &str, key_combination: &str) -> Result<(), HotkeyParseError> {
+        parse_key_combination(key_combination)?;
+        self.keymap.update(|keymap| {
+            keymap.insert(action.to_string(), key_combination.to_string());
+        });
+        self.save_keymap();
+        Ok(())
+    }
+
+    /// Binds `action` back to the key combination it was registered with, and
+    /// saves the keymap.
+    pub fn reset_binding(&self, action: &str) {
+        self.keymap.update(|keymap| {
+            keymap.remove(action);
+        });
+        self.save_keymap();
+    }
+
+    /// Rebinds every action of `keymap` that has bindings, and saves the
+    /// keymap.
+    pub fn import_keymap(&self, keymap: &Keymap) {
+        self.keymap.update(|overrides| {
+            for (action, entry) in &keymap.actions {
+                if let Some(key_combination) = entry.key_combination() {
+                    overrides.insert(action.clone(), key_combination);
+                }
+            }
+        });
+        self.save_keymap();
+    }
+
+    /// The named actions currently registered, with their hotkeys after the
+    /// user's overrides.
+    pub fn export_keymap(&self) -> Keymap {
+        let actions = self.bindings.with_untracked(|bindings| {
+            bindings
+                .values()
+                .filter_map(|binding| {
+                    let mut bindings = binding.hotkeys.iter().cloned().collect::<Vec<_>>();
+                    bindings.sort_by_key(ToString::to_string);
+                    let entry = KeymapEntry {
+                        bindings,
+                        scopes: binding.scopes.clone(),
+                        when: binding.when.as_ref().map(ToString::to_string),
+                    };
+                    Some((binding.action.clone()?, entry))
+                })
+                .collect()
+        });
+        Keymap { actions }
+    }
+
+    /// Loads the keymap from `storage`, and saves it there when it changes.
+    pub fn set_keymap_storage(&self, storage: impl KeymapStorage + 'static) {
+        self.keymap.set(storage.load());
+        self.keymap_storage.set_value(Some(Rc::new(storage)));
+    }
+
+    fn save_keymap(&self) {
+        self.keymap_storage.with_value(|storage| {
+            if let Some(storage) = storage {
+                self.keymap.with_untracked(|keymap| storage.save(keymap));
+            }
+        });
+    }
+
+    /// Hotkeys of `action`: the user's choice if it's valid, `default` otherwise.
+    #[cfg_attr(feature = "ssr", allow(dead_code))]
+    pub(crate) fn action_hotkeys(
+        &self,
+        action: &str,
+        default: &HashSet<Hotkey>,
+    ) -> HashSet<Hotkey> {
+        let key_combination = self.keymap.with(|keymap| keymap.get(action).cloned());
+        match key_combination.map(|key_combination| parse_key_combination(&key_combination)) {
+            Some(Ok(hotkeys)) => hotkeys,
+            Some(Err(err)) => {
+                logging::error!("invalid hotkey for action {:?}: {}", action, err);
+                default.clone()
+            }
+            None => default.clone(),
+        }
+    }
+
+    /// Sets the context key `name` used in when clauses, like `"modalOpen"`,
+    /// to follow `value` until the current reactive owner is cleaned up.
+    pub fn set_context_key(&self, name: impl Into<String>, value: impl Into<Signal<bool>>) {
+        let name = name.into();
+        let id = self.next_id();
+        self.context_keys.update(|context_keys| {
+            context_keys.insert(name.clone(), (id, value.into()));
+        });
+
+        // A later owner may have set the same key since, so only remove the
+        // entry this call inserted.
+        let context_keys = self.context_keys;
+        on_cleanup(move || {
+            context_keys.try_update(|context_keys| {
+                if context_keys
+                    .get(&name)
+                    .is_some_and(|(current, _)| *current == id)
+                {
+                    context_keys.remove(&name);
+                }
+            });
+        });
+    }
+
+    /// Whether a binding in `scopes` gated by `when` can fire, tracking the
+    /// active scopes and context keys.
+    #[cfg_attr(feature = "ssr", allow(dead_code))]
+    pub(crate) fn is_active(&self, scopes: &[Scope], when: Option<&WhenClause>) -> bool {
+        self.with_conditions(|conditions| conditions.allow(scopes, when))
+    }
+
+    /// Runs `f` with the active scopes and context keys, tracking them.
+    pub(crate) fn with_conditions<R>(&self, f: impl FnOnce(&Conditions) -> R) -> R {
+        with_conditions(self.active_scopes, self.true_context_keys, f)
+    }
+
+    /// Whether the binding `id` is registered, not paused and enabled, without
+    /// tracking.
+    #[cfg_attr(feature = "ssr", allow(dead_code))]
+    pub(crate) fn is_running(&self, id: usize) -> bool {
+        untrack(|| {
+            self.bindings
+                .with(|bindings| bindings.get(&id).is_some_and(Binding::is_enabled))
+        })
+    }
+
+    /// Adds `binding` to the registry until the current reactive owner is
+    /// cleaned up, and returns its unique identifier.
+    pub(crate) fn register(&self, binding: Binding) -> usize {
+        let id = self.next_id();
+        self.bindings.update(|bindings| {
+            bindings.insert(id, binding);
+        });
+
+        let bindings = self.bindings;
+        on_cleanup(move || {
+            bindings.try_update(|bindings| bindings.remove(&id));
+        });
+        id
+    }
+
+    /// Returns a fresh identifier for a binding or context key entry.
+    fn next_id(&self) -> usize {
+        let id = self.next_id.get_value();
+        self.next_id.set_value(id + 1);
+        id
+    }
 }
 #[derive(Debug, Default, Clone)]
 #[cfg_attr(feature = "ssr", allow(dead_code))]
 pub struct KeyPresses {
+    /// Keys held down, by lowercased `code`.
     pub key_map: BTreeMap<String, web_sys::KeyboardEvent>,
+    /// Lowercased `code` of the last key pressed, until a key is released.
     pub last_key: Option<String>,
+    /// Last key released, until a key is pressed.
+    pub released: Option<web_sys::KeyboardEvent>,
+}
+
+#[cfg_attr(feature = "ssr", allow(dead_code))]
+impl KeyPresses {
+    pub(crate) fn last_event(&self) -> Option<&web_sys::KeyboardEvent> {
+        self.last_key.as_ref().and_then(|key| self.key_map.get(key))
+    }
+
+    /// Held key press of `key`, a key name of a hotkey.
+    pub(crate) fn find(&self, key: &str, key_mode: KeyMode) -> Option<&web_sys::KeyboardEvent> {
+        self.key_map
+            .values()
+            .find(|event| is_key_event(key, event, key_mode))
+    }
 }
 
 pub fn provide_hotkeys_context<T>(
     #[cfg_attr(feature = "ssr", allow(unused_variables))] node_ref: NodeRef<T>,
     #[cfg_attr(feature = "ssr", allow(unused_variables))] allow_blur_event: bool,
-    initially_active_scopes: HashSet<String>,
+    initially_active_scopes: impl IntoIterator<Item = impl HotkeyScope>,
 ) -> HotkeysContext
 where
     T: ElementDescriptor + 'static + Clone,
@@ -46,9 +422,25 @@ where
     #[cfg(not(feature = "ssr"))]
     let keys_pressed: RwSignal<KeyPresses> = RwSignal::new(KeyPresses::default());
 
-    let active_scopes: RwSignal<HashSet<String>> = RwSignal::new(initially_active_scopes);
+    let active_scopes: RwSignal<HashSet<Scope>> = RwSignal::new(
+        initially_active_scopes
+            .into_iter()
+            .map(HotkeyScope::into_scope)
+            .collect(),
+    );
+
+    let scope_stack: RwSignal<Vec<PushedScope>> = RwSignal::new(Vec::new());
+    // A scope disabled while an exclusive push suppresses it stays disabled
+    // when that push is popped.
+    let unsuppress = move |scope: &Scope| {
+        scope_stack.update_untracked(|stack| {
+            for pushed in stack {
+                pushed.suppressed.remove(scope);
+            }
+        });
+    };
 
-    let enable_scope = Callback::new(move |scope: String| {
+    let enable_scope = Callback::new(move |scope: Scope| {
         active_scopes.update(|scopes| {
             if !scopes.contains(&scope) {
                 if cfg!(feature = "debug") {
@@ -59,21 +451,23 @@ where
         });
     });
 
-    let disable_scope = Callback::new(move |scope: String| {
+    let disable_scope = Callback::new(move |scope: Scope| {
         active_scopes.update(|scopes| {
             if cfg!(feature = "debug") {
                 logging::log!("removing scope {}", &scope);
             }
+            unsuppress(&scope);
             scopes.remove(&scope);
         })
     });
 
-    let toggle_scope = Callback::new(move |scope: String| {
+    let toggle_scope = Callback::new(move |scope: Scope| {
         active_scopes.update(|scopes| {
             if scopes.contains(&scope) {
                 if cfg!(feature = "debug") {
                     logging::log!("removing scope {}", &scope);
                 }
+                unsuppress(&scope);
                 scopes.remove(&scope);
             } else {
                 if cfg!(feature = "debug") {
@@ -84,6 +478,63 @@ where
         })
     });
 
+    let sequence_timeout = RwSignal::new(DEFAULT_SEQUENCE_TIMEOUT);
+    let sequence_progress: RwSignal<BTreeMap<usize, Vec<Hotkey>>> = RwSignal::new(BTreeMap::new());
+    let pending_sequence = Signal::from(create_memo(move |_| {
+        sequence_progress.with(|progress| {
+            progress
+                .values()
+                .max_by_key(|chords| chords.len())
+                .cloned()
+                .unwrap_or_default()
+        })
+    }));
+
+    let platform = RwSignal::new(Platform::detect());
+    let bindings: RwSignal<BTreeMap<usize, Binding>> = RwSignal::new(BTreeMap::new());
+    let context_keys: RwSignal<HashMap<String, (usize, Signal<bool>)>> =
+        RwSignal::new(HashMap::new());
+    let true_context_keys = create_memo(move |_| {
+        context_keys.with(|context_keys| {
+            context_keys
+                .iter()
+                .filter(|(_, (_, value))| value.get())
+                .map(|(name, _)| name.clone())
+                .collect::<HashSet<_>>()
+        })
+    });
+    let conflicts = Signal::from(create_memo(move |_| {
+        bindings.with(|bindings| {
+            with_conditions(active_scopes, true_context_keys, |conditions| {
+                find_conflicts(bindings, conditions, platform.get())
+            })
+        })
+    }));
+
+    let registry = Signal::from(create_memo(move |_| {
+        bindings.with(|bindings| {
+            with_conditions(active_scopes, true_context_keys, |conditions| {
+                bindings
+                    .iter()
+                    .map(|(id, binding)| RegisteredHotkey::new(*id, binding, conditions))
+                    .collect()
+            })
+        })
+    }));
+
+    #[cfg(all(feature = "debug", not(feature = "ssr")))]
+    create_effect(move |_| {
+        for conflict in conflicts.get() {
+            logging::warn!(
+                "hotkeys {} in scopes {:?} and {} in scopes {:?} overlap without a priority",
+                conflict.hotkeys[0],
+                conflict.scopes[0],
+                conflict.hotkeys[1],
+                conflict.scopes[1]
+            );
+        }
+    });
+
     #[cfg(all(feature = "debug", not(feature = "ssr")))]
     create_effect(move |_| {
         let keys_pressed_list = move || {
@@ -103,7 +554,7 @@ where
             if cfg!(feature = "debug") {
                 logging::log!("Window lost focus");
             }
-            keys_pressed.set_untracked(KeyPresses::default());
+            keys_pressed.set(KeyPresses::default());
         }) as Box<dyn Fn()>);
 
         let keydown_listener =
@@ -112,6 +563,7 @@ where
                     let key = clean_key(&event);
                     keys.key_map.insert(key.clone(), event);
                     keys.last_key = Some(key);
+                    keys.released = None;
                 });
             }) as Box<dyn Fn(_)>);
         let keyup_listener =
@@ -120,6 +572,7 @@ where
                     let key = clean_key(&event);
                     keys.key_map.remove(&key);
                     keys.last_key = None;
+                    keys.released = Some(event);
                 });
             }) as Box<dyn Fn(_)>);
 
@@ -178,24 +631,244 @@ where
         enable_scope,
         disable_scope,
         toggle_scope,
+
+        sequence_timeout,
+        pending_sequence,
+        platform,
+        key_mode: RwSignal::new(KeyMode::default()),
+        sequence_progress,
+        bindings,
+        scope_stack,
+        scope_users: StoredValue::new(HashMap::new()),
+        context_keys,
+        true_context_keys,
+        conflicts,
+        registry,
+        keymap: RwSignal::new(KeymapOverrides::new()),
+        keymap_storage: StoredValue::new(None),
+        next_id: StoredValue::new(0),
     };
 
     provide_context(hotkeys_context);
     hotkeys_context
 }
 
+fn with_conditions<R>(
+    active_scopes: RwSignal<HashSet<Scope>>,
+    true_context_keys: Memo<HashSet<String>>,
+    f: impl FnOnce(&Conditions) -> R,
+) -> R {
+    active_scopes.with(|active_scopes| {
+        true_context_keys.with(|context_keys| {
+            f(&Conditions {
+                active_scopes,
+                context_keys,
+            })
+        })
+    })
+}
+
 pub fn use_hotkeys_context() -> HotkeysContext {
     use_context::<HotkeysContext>().expect("expected hotkeys context")
 }
 
+/// Identifies a held key by its `code`, which unlike `key` doesn't change
+/// between `keydown` and `keyup` when modifiers are released first.
 #[cfg(not(feature = "ssr"))]
 fn clean_key(event: &web_sys::KeyboardEvent) -> String {
-    if cfg!(feature = "use_key") {
-        match event.key().as_str() {
-            " " => "spacebar".to_string(),
-            key => key.to_lowercase(),
-        }
-    } else {
-        event.code().to_lowercase()
+    match event.code().as_str() {
+        "" => event.key().to_lowercase(),
+        code => code.to_lowercase(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scopes;
+
+    fn provide_context() -> HotkeysContext {
+        provide_hotkeys_context(NodeRef::<html::Div>::new(), false, scopes!("editor"))
+    }
+
+    fn active_scopes(context: &HotkeysContext) -> Vec<String> {
+        let mut scopes = context
+            .active_scopes
+            .get_untracked()
+            .into_iter()
+            .map(String::from)
+            .collect::<Vec<_>>();
+        scopes.sort();
+        scopes
+    }
+
+    #[test]
+    fn push_and_pop_scopes() {
+        let runtime = create_runtime();
+        let context = provide_context();
+
+        context.push_scope("menu", false);
+        assert_eq!(active_scopes(&context), ["*", "editor", "menu"]);
+        context.push_scope("modal", true);
+        assert_eq!(active_scopes(&context), ["modal"]);
+        assert_eq!(
+            context.scope_stack().get_untracked(),
+            [Scope::from("menu"), Scope::from("modal")]
+        );
+
+        context.with_conditions(|conditions| {
+            assert!(conditions.allow(&[Scope::from("modal")], None));
+            assert!(!conditions.allow(&[Scope::GLOBAL], None));
+            assert!(!conditions.allow(&[Scope::from("editor")], None));
+        });
+
+        assert_eq!(context.pop_scope(), Some(Scope::from("modal")));
+        assert_eq!(active_scopes(&context), ["*", "editor", "menu"]);
+        assert_eq!(context.pop_scope(), Some(Scope::from("menu")));
+        assert_eq!(active_scopes(&context), ["*", "editor"]);
+        assert_eq!(context.pop_scope(), None);
+
+        runtime.dispose();
+    }
+
+    #[test]
+    fn retained_scopes() {
+        let runtime = create_runtime();
+        let context = provide_context();
+
+        context.retain_scope(Scope::from("sidebar"));
+        context.retain_scope(Scope::from("sidebar"));
+        context.release_scope(Scope::from("sidebar"));
+        assert_eq!(active_scopes(&context), ["*", "editor", "sidebar"]);
+        context.release_scope(Scope::from("sidebar"));
+        assert_eq!(active_scopes(&context), ["*", "editor"]);
+
+        // retained while a scope is pushed, kept when it's popped
+        context.push_scope("modal", true);
+        context.retain_scope(Scope::from("sidebar"));
+        assert_eq!(active_scopes(&context), ["modal", "sidebar"]);
+        context.pop_scope();
+        assert_eq!(active_scopes(&context), ["*", "editor", "sidebar"]);
+
+        // released while a scope is pushed, not restored when it's popped
+        context.push_scope("modal", false);
+        context.release_scope(Scope::from("sidebar"));
+        assert_eq!(active_scopes(&context), ["*", "editor", "modal"]);
+        context.pop_scope();
+        assert_eq!(active_scopes(&context), ["*", "editor"]);
+
+        runtime.dispose();
+    }
+
+    #[test]
+    fn pop_scopes_by_name() {
+        let runtime = create_runtime();
+        let context = provide_context();
+
+        context.push_scope("palette", true);
+        context.push_scope("modal", true);
+        assert_eq!(
+            context.pop_scope_named("palette"),
+            Some(Scope::from("palette"))
+        );
+        assert_eq!(active_scopes(&context), ["modal"]);
+        assert_eq!(
+            context.scope_stack().get_untracked(),
+            [Scope::from("modal")]
+        );
+        assert_eq!(context.pop_scope_named("palette"), None);
+        context.pop_scope();
+        assert_eq!(active_scopes(&context), ["*", "editor"]);
+
+        context.push_scope("menu", false);
+        context.push_scope("palette", true);
+        context.pop_scope_named("menu");
+        assert_eq!(active_scopes(&context), ["palette"]);
+        context.pop_scope();
+        assert_eq!(active_scopes(&context), ["*", "editor"]);
+
+        runtime.dispose();
+    }
+
+    #[test]
+    fn pop_keeps_scopes_changed_while_pushed() {
+        let runtime = create_runtime();
+        let context = provide_context();
+
+        context.push_scope("menu", false);
+        Callable::call(&context.enable_scope, Scope::from("search"));
+        Callable::call(&context.disable_scope, Scope::from("editor"));
+        context.pop_scope();
+        assert_eq!(active_scopes(&context), ["*", "search"]);
+
+        // suppressed scopes come back unless they were disabled meanwhile
+        context.push_scope("modal", true);
+        Callable::call(&context.disable_scope, Scope::from("search"));
+        Callable::call(&context.toggle_scope, Scope::from("editor"));
+        assert_eq!(active_scopes(&context), ["editor", "modal"]);
+        context.pop_scope();
+        assert_eq!(active_scopes(&context), ["*", "editor"]);
+
+        // a scope that was already active stays active
+        context.push_scope("editor", true);
+        context.pop_scope();
+        assert_eq!(active_scopes(&context), ["*", "editor"]);
+
+        runtime.dispose();
+    }
+
+    #[test]
+    fn scopes_released_after_the_context() {
+        let runtime = create_runtime();
+        let remount = create_trigger();
+        let context = StoredValue::new(None);
+        create_effect(move |mounted: Option<()>| {
+            remount.track();
+            context.set_value(Some(provide_context()));
+            // only the first mount uses the scope
+            if mounted.is_none() {
+                create_effect(|_| crate::use_scope("sidebar"));
+            }
+        });
+        let first = context.get_value().unwrap();
+        assert_eq!(active_scopes(&first), ["*", "editor", "sidebar"]);
+
+        // the context is disposed before the component using the scope, whose
+        // release leaves the new context alone
+        remount.notify();
+        let context = context.get_value().unwrap();
+        assert_eq!(active_scopes(&context), ["*", "editor"]);
+        assert!(context
+            .scope_users
+            .with_value(|users| !users.contains_key(&Scope::from("sidebar"))));
+
+        runtime.dispose();
+    }
+
+    #[test]
+    fn context_keys_kept_for_the_latest_owner() {
+        let runtime = create_runtime();
+        let context = provide_context();
+        let true_context_keys = |context: &HotkeysContext| {
+            context
+                .true_context_keys
+                .get_untracked()
+                .into_iter()
+                .collect::<Vec<_>>()
+        };
+
+        let set_modal_open = as_child_of_current_owner(move |value: bool| {
+            context.set_context_key("modalOpen", Signal::derive(move || value));
+        });
+        let ((), first) = set_modal_open(false);
+        let ((), second) = set_modal_open(true);
+        assert_eq!(true_context_keys(&context), ["modalOpen"]);
+
+        drop(first);
+        assert_eq!(true_context_keys(&context), ["modalOpen"]);
+        drop(second);
+        assert!(true_context_keys(&context).is_empty());
+
+        runtime.dispose();
     }
 }
@@ -0,0 +1,94 @@
+use crate::Hotkey;
+
+/// Payload passed to the callback of a binding when it fires.
+///
+/// A binding registered for several hotkeys, like
+/// `"arrowup,arrowdown,arrowleft,arrowright"`, can tell from [`hotkey`](Self::hotkey)
+/// which of them was pressed.
+#[derive(Debug, Clone)]
+pub struct HotkeyEvent {
+    /// Hotkey of the binding that was matched.
+    pub hotkey: Hotkey,
+    /// Key press or release the binding fires for, or `None` when it was run
+    /// with [`HotkeysContext::trigger`](crate::HotkeysContext::trigger), or
+    /// when a held hotkey is released because the window lost focus or the
+    /// binding stopped running.
+    pub event: Option<web_sys::KeyboardEvent>,
+    /// Whether the hotkey was released rather than pressed, for bindings
+    /// firing on keyup.
+    pub released: bool,
+    /// Whether the key is held down and the press is repeated.
+    pub repeat: bool,
+    /// Time of the key press in milliseconds, as in `KeyboardEvent.timeStamp`.
+    pub time_stamp: f64,
+}
+
+impl HotkeyEvent {
+    pub(crate) fn new(hotkey: Hotkey, event: Option<web_sys::KeyboardEvent>) -> Self {
+        let (released, repeat, time_stamp) = match &event {
+            Some(event) => (event.type_() == "keyup", event.repeat(), event.time_stamp()),
+            None => (false, false, now()),
+        };
+        HotkeyEvent {
+            hotkey,
+            event,
+            released,
+            repeat,
+            time_stamp,
+        }
+    }
+
+    /// Release of `hotkey` without a keyboard event.
+    #[cfg_attr(feature = "ssr", allow(dead_code))]
+    pub(crate) fn synthetic_release(hotkey: Hotkey) -> Self {
+        HotkeyEvent {
+            hotkey,
+            event: None,
+            released: true,
+            repeat: false,
+            time_stamp: now(),
+        }
+    }
+}
+
+/// Current time on the clock of `KeyboardEvent.timeStamp`, or `0.0` outside
+/// of a browser.
+fn now() -> f64 {
+    if !cfg!(target_arch = "wasm32") {
+        return 0.0;
+    }
+    web_sys::window()
+        .and_then(|window| window.performance())
+        .map_or(0.0, |performance| performance.now())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{provide_hotkeys_context, use_hotkeys_scoped, Hotkey, HotkeyEvent};
+    use leptos::*;
+
+    #[test]
+    fn triggered_event() {
+        let runtime = create_runtime();
+        let hotkeys_context = provide_hotkeys_context(NodeRef::<html::Div>::new(), false, ["*"]);
+        let events = store_value(Vec::<HotkeyEvent>::new());
+        let handle = use_hotkeys_scoped(
+            "ctrl+s,meta+s",
+            Callback::new(move |event| events.update_value(|events| events.push(event))),
+            ["*"],
+        );
+
+        hotkeys_context.trigger(handle.id().unwrap());
+        hotkeys_context.trigger(42);
+        events.with_value(|events| {
+            let [event] = events.as_slice() else {
+                panic!("expected one event, got {events:?}");
+            };
+            assert_eq!(event.hotkey, Hotkey::new("ctrl+s"));
+            assert!(event.event.is_none());
+            assert!(!event.released && !event.repeat);
+        });
+
+        runtime.dispose();
+    }
+}
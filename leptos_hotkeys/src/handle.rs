@@ -0,0 +1,119 @@
+use crate::HotkeysContext;
+use leptos::*;
+
+/// Handle to a binding returned by the `use_hotkeys*` macros and functions,
+/// to turn it off and on without unmounting its component.
+///
+/// The binding is still removed when its reactive owner is cleaned up.
+#[derive(Clone, Copy)]
+pub struct HotkeyHandle {
+    id: Option<usize>,
+    hotkeys_context: HotkeysContext,
+}
+
+impl HotkeyHandle {
+    pub(crate) fn new(hotkeys_context: HotkeysContext, id: usize) -> Self {
+        HotkeyHandle {
+            id: Some(id),
+            hotkeys_context,
+        }
+    }
+
+    /// Handle of a binding that couldn't be registered, which does nothing.
+    pub(crate) fn unregistered(hotkeys_context: HotkeysContext) -> Self {
+        HotkeyHandle {
+            id: None,
+            hotkeys_context,
+        }
+    }
+
+    /// Identifier of the binding in [`HotkeysContext::registry`], or `None` if
+    /// its key combination was invalid.
+    pub fn id(&self) -> Option<usize> {
+        self.id
+    }
+
+    /// Stops the binding from firing until [`resume`](Self::resume).
+    pub fn pause(&self) {
+        self.set_paused(true);
+    }
+
+    /// Lets a paused binding fire again.
+    pub fn resume(&self) {
+        self.set_paused(false);
+    }
+
+    /// Removes the binding for good, before its reactive owner is cleaned up.
+    pub fn dispose(&self) {
+        if let Some(id) = self.id {
+            self.hotkeys_context.bindings.try_update(|bindings| {
+                bindings.remove(&id);
+            });
+        }
+    }
+
+    /// Whether the binding can fire: registered, not paused nor disposed, and
+    /// with an active scope.
+    pub fn is_active(&self) -> Signal<bool> {
+        let id = self.id;
+        let registry = self.hotkeys_context.registry();
+        Signal::derive(move || {
+            registry.with(|registry| {
+                registry
+                    .iter()
+                    .any(|binding| Some(binding.id) == id && binding.active)
+            })
+        })
+    }
+
+    fn set_paused(&self, paused: bool) {
+        let Some(id) = self.id else {
+            return;
+        };
+        self.hotkeys_context.bindings.update(|bindings| {
+            if let Some(binding) = bindings.get_mut(&id) {
+                binding.paused = paused;
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{provide_hotkeys_context, scopes, use_hotkeys_scoped, Scope};
+    use leptos::*;
+
+    #[test]
+    fn pause_resume_and_dispose() {
+        let runtime = create_runtime();
+        let hotkeys_context =
+            provide_hotkeys_context(NodeRef::<html::Div>::new(), false, scopes!("editor"));
+        let handle = use_hotkeys_scoped("ctrl+k", Callback::new(|_| ()), vec!["editor"]);
+        let is_active = handle.is_active();
+        assert_eq!(handle.id(), Some(0));
+        assert!(is_active.get_untracked());
+
+        handle.pause();
+        assert!(!is_active.get_untracked());
+        handle.resume();
+        assert!(is_active.get_untracked());
+
+        Callable::call(&hotkeys_context.disable_scope, Scope::from("editor"));
+        assert!(!is_active.get_untracked());
+        Callable::call(&hotkeys_context.enable_scope, Scope::from("editor"));
+        assert!(is_active.get_untracked());
+
+        handle.dispose();
+        assert!(!is_active.get_untracked());
+        assert!(hotkeys_context.registry().get_untracked().is_empty());
+        handle.resume();
+        assert!(!is_active.get_untracked());
+
+        let unregistered = use_hotkeys_scoped("ctlr+k", Callback::new(|_| ()), vec!["editor"]);
+        assert_eq!(unregistered.id(), None);
+        unregistered.pause();
+        assert!(!unregistered.is_active().get_untracked());
+
+        runtime.dispose();
+    }
+}
@@ -1,17 +1,26 @@
-use crate::KeyboardModifiers;
-use crate::{context::KeyPresses, types::Keys};
+use crate::context::KeyPresses;
+use crate::types::{event_value, Keys};
+use crate::{KeyMode, ModifierSide};
+use crate::{KeyboardModifiers, Platform};
 use core::str::FromStr;
+use leptos_hotkeys_grammar::{parse_list, parse_sequence, Chord, HotkeyParseError, Modifier};
 use std::collections::HashSet;
 use std::fmt::{Display, Formatter};
 
-#[derive(Debug, PartialEq, Hash, Eq)]
+#[derive(Debug, Clone, PartialEq, Hash, Eq)]
 pub struct Hotkey {
     pub(crate) modifiers: KeyboardModifiers,
     pub(crate) keys: Keys,
+    /// Chords that must be pressed in order before this one, as in `ctrl+k ctrl+s`.
+    pub(crate) prefix: Vec<Hotkey>,
 }
 
 impl Display for Hotkey {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for chord in &self.prefix {
+            write!(f, "{} ", chord)?;
+        }
+
         let keys = self
             .keys
             .iter()
@@ -27,126 +36,529 @@ impl Display for Hotkey {
 }
 
 impl Hotkey {
+    /// Parses a key combination like `"ctrl+shift+k"`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key_combination` is not valid, use [`str::parse`] to handle
+    /// the [`HotkeyParseError`] instead.
     pub fn new(key_combination: &str) -> Self {
-        key_combination.parse().unwrap()
+        key_combination
+            .parse()
+            .unwrap_or_else(|err| panic!("invalid hotkey {:?}: {}", key_combination, err))
+    }
+
+    /// Whether `event` is the press of one of the keys of this chord.
+    #[cfg_attr(feature = "ssr", allow(dead_code))]
+    pub(crate) fn includes_key(&self, event: &web_sys::KeyboardEvent, key_mode: KeyMode) -> bool {
+        self.keys
+            .iter()
+            .any(|key| is_key_event(key, event, key_mode))
+    }
+
+    /// Whether releasing a key ends this chord held down: the key is one of its
+    /// keys, or a modifier it requires. `key` and `code` are the lowercased
+    /// values of the keyup event, as compared with key names.
+    #[cfg_attr(feature = "ssr", allow(dead_code))]
+    pub(crate) fn is_released_by(
+        &self,
+        key: &str,
+        code: &str,
+        key_mode: KeyMode,
+        platform: Platform,
+    ) -> bool {
+        let includes_key = self.keys.iter().any(|name| {
+            let (mode, name) = KeyMode::split(name);
+            match mode.unwrap_or(key_mode) {
+                KeyMode::Key => key == name,
+                KeyMode::Code => code == name,
+            }
+        });
+
+        let modifiers = self.modifiers.resolve(platform);
+        let (required, side) = match Modifier::from_name(key).or(Modifier::from_name(code)) {
+            Some(Modifier::Alt) => (modifiers.alt, modifiers.alt_side),
+            Some(Modifier::Ctrl) => (modifiers.ctrl, modifiers.ctrl_side),
+            Some(Modifier::Meta) => (modifiers.meta, modifiers.meta_side),
+            Some(Modifier::Shift) => (modifiers.shift, modifiers.shift_side),
+            _ => (false, ModifierSide::Any),
+        };
+        let released_side = ModifierSide::from_name(code);
+        let same_side = side == ModifierSide::Any
+            || released_side == ModifierSide::Any
+            || side == released_side;
+
+        includes_key || required && same_side
+    }
+
+    /// Replaces the platform dependent `mod` modifier with the one it stands
+    /// for on `platform`, to render it for example.
+    pub fn resolve(&self, platform: Platform) -> Hotkey {
+        Hotkey {
+            modifiers: self.modifiers.resolve(platform),
+            keys: self.keys.clone(),
+            prefix: self
+                .prefix
+                .iter()
+                .map(|chord| chord.resolve(platform))
+                .collect(),
+        }
+    }
+
+    /// Label of this hotkey to show to users, like `⌘⇧K` on Apple platforms
+    /// and `Ctrl+Shift+K` elsewhere: modifiers first and resolved for
+    /// `platform`, keys without their `key:` or `code:` prefix.
+    pub fn label(&self, platform: Platform) -> String {
+        self.prefix
+            .iter()
+            .chain([self])
+            .map(|chord| {
+                let modifiers = chord.modifiers.labels(platform);
+                let keys = chord.keys.iter().map(|key| key_label(key, platform));
+                let labels = modifiers.into_iter().chain(keys).collect::<Vec<_>>();
+                match platform {
+                    Platform::Apple => labels.concat(),
+                    Platform::Other => labels.join("+"),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Whether this hotkey and `other`, both resolved for a platform, can fire
+    /// on the same key presses: their chords match the same keys, or one is a
+    /// sequence starting with the chords of the other.
+    ///
+    /// Keys named in different modes, like `key:a` and `code:keya`, are not
+    /// compared, as whether they match the same key depends on the layout.
+    pub(crate) fn overlaps(&self, other: &Hotkey) -> bool {
+        let chords = self.prefix.iter().chain([self]);
+        let other_chords = other.prefix.iter().chain([other]);
+        chords
+            .zip(other_chords)
+            .all(|(chord, other_chord)| chord.chord_overlaps(other_chord))
+    }
+
+    /// Whether the last chords of this hotkey and `other` match the same keys.
+    fn chord_overlaps(&self, other: &Hotkey) -> bool {
+        self.modifiers.overlaps(&other.modifiers)
+            && self.keys.len() == other.keys.len()
+            && self.keys.iter().all(|key| {
+                other
+                    .keys
+                    .iter()
+                    .any(|other_key| is_same_key(key, other_key))
+            })
+    }
+
+    /// Whether this hotkey is a sequence of more than one chord.
+    #[cfg_attr(feature = "ssr", allow(dead_code))]
+    pub(crate) fn is_sequence(&self) -> bool {
+        !self.prefix.is_empty()
     }
 
-    fn includes_key(&self, key: &String) -> bool {
-        self.keys.iter().any(|k| k == key)
+    /// Number of chords in the sequence.
+    fn len(&self) -> usize {
+        self.prefix.len() + 1
     }
+
+    /// Chord at `index` of the sequence. Only its modifiers and keys are
+    /// meaningful.
+    fn chord(&self, index: usize) -> &Hotkey {
+        self.prefix.get(index).unwrap_or(self)
+    }
+
+    fn from_chords(chords: impl IntoIterator<Item = Chord>) -> Self {
+        let mut chords = chords
+            .into_iter()
+            .map(|chord| {
+                let mut modifiers = KeyboardModifiers::default();
+                for (modifier, side) in chord.modifiers {
+                    match modifier {
+                        Modifier::Alt => (modifiers.alt, modifiers.alt_side) = (true, side),
+                        Modifier::Ctrl => (modifiers.ctrl, modifiers.ctrl_side) = (true, side),
+                        Modifier::Meta => (modifiers.meta, modifiers.meta_side) = (true, side),
+                        Modifier::Shift => (modifiers.shift, modifiers.shift_side) = (true, side),
+                        Modifier::Primary => modifiers.primary = true,
+                        // states are collected separately by the parser
+                        Modifier::AltGraph
+                        | Modifier::CapsLock
+                        | Modifier::Fn
+                        | Modifier::NumLock => {}
+                    }
+                }
+                for (modifier, active) in chord.states {
+                    match modifier {
+                        Modifier::AltGraph => modifiers.alt_graph = Some(active),
+                        Modifier::CapsLock => modifiers.caps_lock = Some(active),
+                        Modifier::Fn => modifiers.fn_key = Some(active),
+                        Modifier::NumLock => modifiers.num_lock = Some(active),
+                        _ => {}
+                    }
+                }
+
+                Hotkey {
+                    modifiers,
+                    keys: chord.keys,
+                    prefix: Vec::new(),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // the parser never returns an empty sequence
+        let mut hotkey = chords.pop().expect("a hotkey has at least one chord");
+        hotkey.prefix = chords;
+        hotkey
+    }
+}
+
+/// A chord parsed at compile time by the `leptos_hotkeys_macros` crate.
+#[doc(hidden)]
+pub struct StaticChord {
+    pub modifiers: &'static [(Modifier, ModifierSide)],
+    pub states: &'static [(Modifier, bool)],
+    pub keys: &'static [&'static str],
+}
+
+/// Builds the hotkeys of a `,`-separated list parsed at compile time.
+#[doc(hidden)]
+pub fn hotkeys_from_static(sequences: &[&[StaticChord]]) -> HashSet<Hotkey> {
+    sequences
+        .iter()
+        .map(|chords| {
+            Hotkey::from_chords(chords.iter().map(|chord| Chord {
+                modifiers: chord.modifiers.to_vec(),
+                states: chord.states.to_vec(),
+                keys: chord.keys.iter().map(|key| key.to_string()).collect(),
+            }))
+        })
+        .collect()
 }
 
 impl FromStr for Hotkey {
-    type Err = ();
+    type Err = HotkeyParseError;
 
     fn from_str(key_combination: &str) -> Result<Self, Self::Err> {
-        let parts = key_combination
-            .split('+')
-            .map(|v| if v == " " { "spacebar" } else { v.trim() })
-            .collect::<Vec<&str>>();
-
-        let mut modifiers = KeyboardModifiers::default();
-        let mut keys = Vec::new();
-
-        for part in parts {
-            match part.to_lowercase().as_str() {
-                "controlleft" => modifiers.ctrl = true,
-                "controlright" => modifiers.ctrl = true,
-                "ctrl" => modifiers.ctrl = true,
-                "control" => modifiers.ctrl = true,
-
-                "alt" => modifiers.alt = true,
-                "altleft" => modifiers.alt = true,
-                "altright" => modifiers.alt = true,
-                "option" => modifiers.alt = true, // macos variant
-
-                "metaleft" => modifiers.meta = true,
-                "metaright" => modifiers.meta = true,
-                "meta" => modifiers.meta = true,
-                "command" => modifiers.meta = true, // macos variant
-                "cmd" => modifiers.meta = true,     // macos variant
-                "super" => modifiers.meta = true,   // linux variant
-                "win" => modifiers.meta = true,     // windows variant
-
-                "shiftleft" => modifiers.shift = true,
-                "shiftright" => modifiers.shift = true,
-                "shift" => modifiers.shift = true,
-
-                "spacebar" | "space" | " " => keys.push("spacebar".to_string()), // spacebar key is " "
-
-                key => keys.push(key.to_lowercase().to_string()),
-            }
-        }
+        Ok(Hotkey::from_chords(parse_sequence(key_combination)?))
+    }
+}
+
+/// Serialized in its canonical string form, like `"k+Ctrl s+Ctrl"`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Hotkey {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Hotkey {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let key_combination = String::deserialize(deserializer)?;
+        key_combination.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Parses a `,`-separated list of key combinations, like `"ctrl+s,meta+s"`.
+///
+/// Spans of the returned error are relative to the whole list.
+pub(crate) fn parse_key_combination(
+    key_combination: &str,
+) -> Result<HashSet<Hotkey>, HotkeyParseError> {
+    Ok(parse_list(key_combination)?
+        .into_iter()
+        .map(Hotkey::from_chords)
+        .collect())
+}
 
-        Ok(Hotkey { modifiers, keys })
+/// Parses a single chord like `"space"` or `"ctrl+k"`, rejecting lists and
+/// sequences.
+pub(crate) fn parse_chord(key_combination: &str) -> Result<Hotkey, HotkeyParseError> {
+    let mut list = parse_list(key_combination)?;
+    match (list.pop(), list.is_empty()) {
+        (Some(chords), true) if chords.len() == 1 => Ok(Hotkey::from_chords(chords)),
+        _ => Err(HotkeyParseError::NotAChord {
+            span: 0..key_combination.len(),
+        }),
     }
 }
 
 #[cfg_attr(feature = "ssr", allow(dead_code))]
-pub(crate) fn is_last_key_match(parsed_keys: &HashSet<Hotkey>, pressed_keys: &KeyPresses) -> bool {
-    pressed_keys.last_key.as_ref().is_some_and(|last_key| {
+pub(crate) fn is_last_key_match<'a>(
+    parsed_keys: impl IntoIterator<Item = &'a Hotkey>,
+    pressed_keys: &KeyPresses,
+    key_mode: KeyMode,
+) -> bool {
+    pressed_keys.last_event().is_some_and(|last_event| {
         parsed_keys
-            .iter()
-            .any(|hotkey| hotkey.includes_key(last_key))
+            .into_iter()
+            .any(|hotkey| hotkey.includes_key(last_event, key_mode))
     })
 }
 
+/// Label of a key name of a hotkey, like `K` for `key:k` or `code:keyk`.
+fn key_label(key: &str, platform: Platform) -> String {
+    let (_, name) = KeyMode::split(key);
+    let apple = platform == Platform::Apple;
+    let label = match name {
+        "arrowup" | "up" => "↑",
+        "arrowdown" | "down" => "↓",
+        "arrowleft" | "left" => "←",
+        "arrowright" | "right" => "→",
+        "space" | "spacebar" | " " => "Space",
+        "escape" | "esc" => "Esc",
+        "enter" | "return" if apple => "↩",
+        "enter" | "return" => "Enter",
+        "backspace" if apple => "⌫",
+        "delete" if apple => "⌦",
+        "tab" if apple => "⇥",
+        "capslock" if apple => "⇪",
+        "capslock" => "Caps Lock",
+        "numlock" => "Num Lock",
+        "pageup" => "Page Up",
+        "pagedown" => "Page Down",
+        "slash" => "/",
+        "backslash" => "\\",
+        "comma" => ",",
+        "period" => ".",
+        "semicolon" => ";",
+        "quote" => "'",
+        "backquote" => "`",
+        "bracketleft" => "[",
+        "bracketright" => "]",
+        "minus" => "-",
+        "equal" => "=",
+        _ => {
+            let name = match name.strip_prefix("key").or(name.strip_prefix("digit")) {
+                Some(character) if character.chars().count() == 1 => character,
+                _ => name,
+            };
+            let mut chars = name.chars();
+            return chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect())
+                .unwrap_or_default();
+        }
+    };
+    label.to_string()
+}
+
+/// Whether the key names `a` and `b` of hotkeys match the same key, a name
+/// without a `key:` or `code:` prefix matching both modes.
+fn is_same_key(a: &str, b: &str) -> bool {
+    let (a_mode, a_name) = KeyMode::split(a);
+    let (b_mode, b_name) = KeyMode::split(b);
+    a_name == b_name && (a_mode.is_none() || b_mode.is_none() || a_mode == b_mode)
+}
+
+/// Whether `event` is the press of `key`, a key name of a hotkey compared in
+/// `key_mode` unless it has a `key:` or `code:` prefix.
+#[cfg_attr(feature = "ssr", allow(dead_code))]
+pub(crate) fn is_key_event(key: &str, event: &web_sys::KeyboardEvent, key_mode: KeyMode) -> bool {
+    let (mode, name) = KeyMode::split(key);
+    event_value(event, mode.unwrap_or(key_mode)) == name
+}
+
+/// Whether `event` is the press of a modifier key, like Shift.
+#[cfg_attr(feature = "ssr", allow(dead_code))]
+pub(crate) fn is_modifier_event(event: &web_sys::KeyboardEvent) -> bool {
+    [KeyMode::Key, KeyMode::Code]
+        .into_iter()
+        .any(|mode| Modifier::from_name(&event_value(event, mode)).is_some())
+}
+
+/// Outcome of feeding a key press to a [`SequenceState`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SequenceStep {
+    /// The key press is not part of the sequence.
+    Ignored,
+    /// The key press matched the next chord, and more chords are expected.
+    Advanced,
+    /// The key press matched the last chord.
+    Completed,
+    /// The key press broke a sequence in progress.
+    Reset,
+}
+
+/// Progress of a key sequence like `ctrl+k ctrl+s`.
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "ssr", allow(dead_code))]
+pub(crate) struct SequenceState {
+    matched: usize,
+    last_step_at: f64,
+    last_event_at: Option<f64>,
+}
+
+#[cfg_attr(feature = "ssr", allow(dead_code))]
+impl SequenceState {
+    /// Number of chords of the sequence matched so far.
+    pub(crate) fn matched(&self) -> usize {
+        self.matched
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.matched = 0;
+    }
+
+    /// Feeds a key press happening at `now` (in milliseconds) to the state.
+    ///
+    /// `is_match` tells whether the pressed keys match a chord of `hotkey`.
+    /// Progress is lost when more than `timeout` milliseconds elapse between
+    /// two chords.
+    pub(crate) fn advance(
+        &mut self,
+        hotkey: &Hotkey,
+        now: f64,
+        timeout: f64,
+        mut is_match: impl FnMut(&Hotkey) -> bool,
+    ) -> SequenceStep {
+        // the same key press can be seen more than once when other signals change
+        if self.last_event_at == Some(now) {
+            return SequenceStep::Ignored;
+        }
+        self.last_event_at = Some(now);
+
+        if self.matched > 0 && now - self.last_step_at > timeout {
+            self.matched = 0;
+        }
+
+        if is_match(hotkey.chord(self.matched)) {
+            self.matched += 1;
+            self.last_step_at = now;
+            if self.matched == hotkey.len() {
+                self.matched = 0;
+                return SequenceStep::Completed;
+            }
+            return SequenceStep::Advanced;
+        }
+
+        if self.matched == 0 {
+            return SequenceStep::Ignored;
+        }
+
+        // the broken sequence may be the start of a new one, as in `g g g i`
+        self.matched = 0;
+        if is_match(hotkey.chord(0)) {
+            self.matched = 1;
+            self.last_step_at = now;
+            return SequenceStep::Advanced;
+        }
+        SequenceStep::Reset
+    }
+}
+
+/// Sides on which a modifier is held down.
+#[cfg_attr(feature = "ssr", allow(dead_code))]
+struct ModifierPresses {
+    left: bool,
+    right: bool,
+    unknown: bool,
+}
+
+#[cfg_attr(feature = "ssr", allow(dead_code))]
+impl ModifierPresses {
+    /// `code` is the `code` value of the modifier without its side, like
+    /// `"control"`, and `names` the `key` values it may be reported as.
+    fn new(pressed_keys: &KeyPresses, code: &str, names: &[&str]) -> Self {
+        let mut presses = ModifierPresses {
+            left: false,
+            right: false,
+            unknown: false,
+        };
+
+        for event in pressed_keys.key_map.values() {
+            let event_code = event_value(event, KeyMode::Code);
+            let side = event_code.strip_prefix(code);
+            let named = names.contains(&event_value(event, KeyMode::Key).as_str());
+            if !named && !matches!(side, Some("left" | "right")) {
+                continue;
+            }
+
+            match (event.location(), side) {
+                (web_sys::KeyboardEvent::DOM_KEY_LOCATION_LEFT, _) | (_, Some("left")) => {
+                    presses.left = true
+                }
+                (web_sys::KeyboardEvent::DOM_KEY_LOCATION_RIGHT, _) | (_, Some("right")) => {
+                    presses.right = true
+                }
+                _ => presses.unknown = true,
+            }
+        }
+
+        presses
+    }
+
+    fn on(&self, side: ModifierSide) -> bool {
+        match side {
+            ModifierSide::Any => self.left || self.right || self.unknown,
+            ModifierSide::Left => self.left,
+            ModifierSide::Right => self.right,
+        }
+    }
+}
+
 #[cfg_attr(feature = "ssr", allow(dead_code))]
 pub(crate) fn is_hotkey_match(
     hotkey: &Hotkey,
-    pressed_keyset: &mut std::collections::BTreeMap<String, web_sys::KeyboardEvent>,
+    pressed_keys: &KeyPresses,
+    platform: Platform,
+    key_mode: KeyMode,
 ) -> bool {
+    let modifiers = hotkey.modifiers.resolve(platform);
     let mut modifiers_match = true;
 
-    let is_ctrl_pressed = pressed_keyset.contains_key("controlleft")
-        || pressed_keyset.contains_key("controlright")
-        || pressed_keyset.contains_key("control");
-    if hotkey.modifiers.ctrl {
-        modifiers_match &= is_ctrl_pressed;
-    } else {
-        modifiers_match &= !is_ctrl_pressed;
-    }
+    let modifier_keys = [
+        (
+            modifiers.ctrl,
+            modifiers.ctrl_side,
+            "control",
+            &["control"][..],
+        ),
+        (modifiers.shift, modifiers.shift_side, "shift", &["shift"]),
+        (
+            modifiers.meta,
+            modifiers.meta_side,
+            "meta",
+            &["meta", "command", "cmd", "super", "win"],
+        ),
+        (modifiers.alt, modifiers.alt_side, "alt", &["alt"]),
+    ];
+    for (required, side, code, names) in modifier_keys {
+        // AltGr is reported as Ctrl+Alt on Windows, and as AltRight by `code`
+        let part_of_alt_graph =
+            modifiers.alt_graph == Some(true) && matches!(code, "control" | "alt");
 
-    let is_shift_pressed = pressed_keyset.contains_key("shiftleft")
-        || pressed_keyset.contains_key("shiftright")
-        || pressed_keyset.contains_key("shift");
-    if hotkey.modifiers.shift {
-        modifiers_match &= is_shift_pressed;
-    } else {
-        modifiers_match &= !is_shift_pressed;
-    }
-
-    let is_meta_pressed = pressed_keyset.contains_key("metaleft")
-        || pressed_keyset.contains_key("metaright")
-        || pressed_keyset.contains_key("meta")
-        || pressed_keyset.contains_key("command")
-        || pressed_keyset.contains_key("cmd")
-        || pressed_keyset.contains_key("super")
-        || pressed_keyset.contains_key("win");
-    if hotkey.modifiers.meta {
-        modifiers_match &= is_meta_pressed;
-    } else {
-        modifiers_match &= !is_meta_pressed;
+        let pressed = ModifierPresses::new(pressed_keys, code, names);
+        if required {
+            modifiers_match &= pressed.on(side);
+        } else if !part_of_alt_graph {
+            modifiers_match &= !pressed.on(ModifierSide::Any);
+        }
     }
 
-    let is_alt_pressed = pressed_keyset.contains_key("altleft")
-        || pressed_keyset.contains_key("altright")
-        || pressed_keyset.contains_key("alt");
-    if hotkey.modifiers.alt {
-        modifiers_match &= is_alt_pressed;
-    } else {
-        modifiers_match &= !is_alt_pressed;
+    let states = [
+        (modifiers.alt_graph, "AltGraph"),
+        (modifiers.caps_lock, "CapsLock"),
+        (modifiers.fn_key, "Fn"),
+        (modifiers.num_lock, "NumLock"),
+    ];
+    // the most recent event carries the current modifier states
+    let latest_event = pressed_keys
+        .key_map
+        .values()
+        .max_by(|a, b| a.time_stamp().total_cmp(&b.time_stamp()));
+    for (state, name) in states {
+        if let Some(active) = state {
+            modifiers_match &=
+                latest_event.is_some_and(|event| event.get_modifier_state(name) == active);
+        }
     }
 
     if modifiers_match {
-        let keys_match = hotkey.keys.iter().all(|key| {
-            if let Some(event) = pressed_keyset.get_mut(key) {
-                event.prevent_default();
-                true
-            } else {
-                false
-            }
-        });
+        let keys_match = hotkey
+            .keys
+            .iter()
+            .all(|key| pressed_keys.find(key, key_mode).is_some());
 
         modifiers_match && keys_match
     } else {
@@ -168,8 +580,10 @@ mod tests {
                         ctrl: false,
                         alt: false,
                         meta: true,
+                        ..Default::default()
                     },
                     keys: vec!["r".into()],
+                    prefix: vec![],
                 },
             ),
             (
@@ -180,8 +594,10 @@ mod tests {
                         ctrl: false,
                         alt: true,
                         meta: false,
+                        ..Default::default()
                     },
                     keys: vec!["o".into(), "t".into()],
+                    prefix: vec![],
                 },
             ),
             (
@@ -192,8 +608,10 @@ mod tests {
                         ctrl: true,
                         alt: false,
                         meta: false,
+                        ..Default::default()
                     },
                     keys: vec!["l".into(), "8".into(), "8".into()],
+                    prefix: vec![],
                 },
             ),
             (
@@ -204,8 +622,10 @@ mod tests {
                         ctrl: true,
                         alt: true,
                         meta: false,
+                        ..Default::default()
                     },
                     keys: vec!["t".into()],
+                    prefix: vec![],
                 },
             ),
             (
@@ -216,8 +636,10 @@ mod tests {
                         ctrl: false,
                         alt: false,
                         meta: true,
+                        ..Default::default()
                     },
                     keys: vec!["k".into()],
+                    prefix: vec![],
                 },
             ),
             (
@@ -228,8 +650,10 @@ mod tests {
                         ctrl: false,
                         alt: false,
                         meta: true,
+                        ..Default::default()
                     },
                     keys: vec!["k".into()],
+                    prefix: vec![],
                 },
             ),
         ]
@@ -253,4 +677,334 @@ mod tests {
             assert_eq!(hotkey, expected);
         }
     }
+
+    #[test]
+    fn hotkey_parse_errors() {
+        let cases = vec![
+            ("", HotkeyParseError::EmptySegment { span: 0..0 }),
+            ("ctrl++s", HotkeyParseError::EmptySegment { span: 5..5 }),
+            (
+                "ctlr+s",
+                HotkeyParseError::UnknownKey {
+                    key: "ctlr".to_string(),
+                    span: 0..4,
+                },
+            ),
+            (
+                "ctrl + controlleft+s",
+                HotkeyParseError::DuplicateModifier {
+                    modifier: "controlleft".to_string(),
+                    span: 7..18,
+                },
+            ),
+            ("shift+meta", HotkeyParseError::ModifierOnly { span: 0..10 }),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(input.parse::<Hotkey>(), Err(expected), "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn released_chords() {
+        let released = |hotkey: &str, key: &str, code: &str| {
+            Hotkey::new(hotkey).is_released_by(key, code, KeyMode::Key, Platform::Other)
+        };
+
+        assert!(released("space", "spacebar", "spacebar"));
+        assert!(released("ctrl+k", "k", "keyk"));
+        assert!(released("ctrl+code:keyk", "k", "keyk"));
+        assert!(released("ctrl+k", "control", "controlright"));
+        assert!(released("mod+k", "control", "controlleft"));
+        assert!(released("controlleft+k", "control", "controlleft"));
+        assert!(released("?+shift", "?", "slash"));
+        assert!(released("capslock", "capslock", "capslock"));
+
+        assert!(!released("space", "shift", "shiftleft"));
+        assert!(!released("ctrl+k", "j", "keyj"));
+        assert!(!released("ctrl+code:keyk", "k", "keyj"));
+        assert!(!released("ctrl+k", "meta", "metaleft"));
+        assert!(!released("controlleft+k", "control", "controlright"));
+        assert!(!released("!capslock+a", "capslock", "capslock"));
+    }
+
+    #[test]
+    fn held_modifier_sides() {
+        let both = ModifierPresses {
+            left: true,
+            right: true,
+            unknown: false,
+        };
+        assert!(both.on(ModifierSide::Any));
+        assert!(both.on(ModifierSide::Left));
+        assert!(both.on(ModifierSide::Right));
+
+        let left = ModifierPresses {
+            left: true,
+            right: false,
+            unknown: false,
+        };
+        assert!(left.on(ModifierSide::Left));
+        assert!(!left.on(ModifierSide::Right));
+
+        let unknown = ModifierPresses {
+            left: false,
+            right: false,
+            unknown: true,
+        };
+        assert!(unknown.on(ModifierSide::Any));
+        assert!(!unknown.on(ModifierSide::Left));
+    }
+
+    #[test]
+    fn single_chord() {
+        assert_eq!(parse_chord("ctrl+k"), Ok(Hotkey::new("ctrl+k")));
+        for input in ["ctrl+k,ctrl+p", "g i"] {
+            assert_eq!(
+                parse_chord(input),
+                Err(HotkeyParseError::NotAChord {
+                    span: 0..input.len()
+                }),
+                "{:?}",
+                input
+            );
+        }
+        assert_eq!(
+            parse_chord("ctrl+"),
+            Err(HotkeyParseError::EmptySegment { span: 5..5 })
+        );
+    }
+
+    #[test]
+    fn key_combination_error_span() {
+        assert_eq!(
+            parse_key_combination("ctrl+s,meta+kye"),
+            Err(HotkeyParseError::UnknownKey {
+                key: "kye".to_string(),
+                span: 12..15,
+            })
+        );
+        assert_eq!(parse_key_combination("ctrl+s,meta+s").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn hotkey_sequence_from_string() {
+        let hotkey: Hotkey = "ctrl+k  Ctrl + S".parse().unwrap();
+        assert_eq!(hotkey.prefix, vec![Hotkey::new("ctrl+k")]);
+        assert_eq!(hotkey.keys, vec!["s".to_string()]);
+        assert!(hotkey.modifiers.ctrl);
+
+        let hotkey: Hotkey = "g g".parse().unwrap();
+        assert_eq!(hotkey.prefix, vec![Hotkey::new("g")]);
+        assert_eq!(hotkey.to_string(), "g g");
+
+        assert_eq!(
+            "ctrl+k ctrl".parse::<Hotkey>(),
+            Err(HotkeyParseError::ModifierOnly { span: 7..11 })
+        );
+    }
+
+    #[test]
+    fn sequence_state() {
+        let hotkey = Hotkey::new("g g i");
+        let mut state = SequenceState::default();
+        let press = |state: &mut SequenceState, key: &str, now: f64| {
+            state.advance(&hotkey, now, 1000.0, |chord| chord.keys[0] == key)
+        };
+
+        assert_eq!(press(&mut state, "x", 0.0), SequenceStep::Ignored);
+        assert_eq!(press(&mut state, "g", 10.0), SequenceStep::Advanced);
+        assert_eq!(press(&mut state, "g", 10.0), SequenceStep::Ignored);
+        assert_eq!(press(&mut state, "g", 20.0), SequenceStep::Advanced);
+        assert_eq!(state.matched(), 2);
+        assert_eq!(press(&mut state, "i", 30.0), SequenceStep::Completed);
+        assert_eq!(state.matched(), 0);
+
+        assert_eq!(press(&mut state, "g", 40.0), SequenceStep::Advanced);
+        assert_eq!(press(&mut state, "x", 50.0), SequenceStep::Reset);
+        assert_eq!(press(&mut state, "g", 60.0), SequenceStep::Advanced);
+        assert_eq!(press(&mut state, "g", 2000.0), SequenceStep::Advanced);
+        assert_eq!(state.matched(), 1);
+    }
+
+    #[test]
+    fn hotkeys_parsed_at_compile_time() {
+        assert_eq!(
+            crate::__private::hotkeys!(crate, "ctrl+k ctrl+s,Meta + S"),
+            parse_key_combination("ctrl+k ctrl+s,Meta + S").unwrap()
+        );
+    }
+
+    #[test]
+    fn primary_modifier() {
+        let hotkey: Hotkey = "mod+s".parse().unwrap();
+        assert!(hotkey.modifiers.primary);
+        assert_eq!(hotkey.to_string(), "s+Mod");
+        assert_eq!(hotkey.resolve(Platform::Apple), Hotkey::new("meta+s"));
+        assert_eq!(hotkey.resolve(Platform::Other), Hotkey::new("ctrl+s"));
+        assert_eq!(
+            Hotkey::new("primary+k mod+ctrl+s").resolve(Platform::Other),
+            Hotkey::new("ctrl+k ctrl+s")
+        );
+    }
+
+    #[test]
+    fn modifier_sides() {
+        let hotkey = Hotkey::new("shiftright+enter");
+        assert_eq!(hotkey.modifiers.shift_side, ModifierSide::Right);
+        assert_eq!(hotkey.to_string(), "enter+ShiftRight");
+        assert_ne!(hotkey, Hotkey::new("shiftleft+enter"));
+        assert_ne!(hotkey, Hotkey::new("shift+enter"));
+
+        let hotkey = Hotkey::new("ctrl+altleft+k");
+        assert_eq!(hotkey.modifiers.ctrl_side, ModifierSide::Any);
+        assert_eq!(hotkey.modifiers.alt_side, ModifierSide::Left);
+        assert_eq!(hotkey.to_string().parse::<Hotkey>(), Ok(hotkey));
+    }
+
+    #[test]
+    fn modifier_states() {
+        let hotkey = Hotkey::new("AltGr+e");
+        assert_eq!(hotkey.modifiers.alt_graph, Some(true));
+        assert_eq!(hotkey.modifiers.caps_lock, None);
+        assert_eq!(hotkey.to_string(), "e+AltGraph");
+
+        let hotkey = Hotkey::new("shift+!capslock+a");
+        assert!(hotkey.modifiers.shift);
+        assert_eq!(hotkey.modifiers.caps_lock, Some(false));
+        assert_eq!(hotkey.to_string().parse::<Hotkey>(), Ok(hotkey));
+
+        assert_eq!(
+            "capslock+!capslock+a".parse::<Hotkey>(),
+            Err(HotkeyParseError::DuplicateModifier {
+                modifier: "!capslock".to_string(),
+                span: 9..18,
+            })
+        );
+        assert_eq!(
+            "!shift+a".parse::<Hotkey>(),
+            Err(HotkeyParseError::UnknownKey {
+                key: "!shift".to_string(),
+                span: 0..6,
+            })
+        );
+        assert_eq!(
+            crate::__private::hotkeys!(crate, "fn+f1,!numlock+numpad1"),
+            parse_key_combination("fn+f1,!numlock+numpad1").unwrap()
+        );
+    }
+
+    #[test]
+    fn labels() {
+        let label = |hotkey: &str, platform| Hotkey::new(hotkey).label(platform);
+
+        assert_eq!(label("shift+key:?", Platform::Other), "Shift+?");
+        assert_eq!(label("s+ctrl", Platform::Other), "Ctrl+S");
+        assert_eq!(
+            label("mod+shift+code:keyk", Platform::Other),
+            "Ctrl+Shift+K"
+        );
+        assert_eq!(label("mod+shift+code:keyk", Platform::Apple), "⇧⌘K");
+        assert_eq!(label("meta+alt+ctrl+digit1", Platform::Apple), "⌃⌥⌘1");
+        assert_eq!(
+            label("controlleft+enter", Platform::Other),
+            "Left Ctrl+Enter"
+        );
+        assert_eq!(label("ctrl+k ctrl+slash", Platform::Other), "Ctrl+K Ctrl+/");
+        assert_eq!(label("g i", Platform::Apple), "G I");
+        assert_eq!(label("!capslock+arrowup", Platform::Other), "↑");
+        assert_eq!(label("capslock", Platform::Apple), "⇪");
+        assert_eq!(label("f5", Platform::Other), "F5");
+    }
+
+    #[test]
+    fn overlapping_hotkeys() {
+        let overlaps = |a: &str, b: &str| Hotkey::new(a).overlaps(&Hotkey::new(b));
+
+        assert!(overlaps("ctrl+k", "ctrl+k"));
+        assert!(overlaps("ctrl+k", "controlleft+k"));
+        assert!(overlaps("code:keyk+ctrl", "ctrl+keyk"));
+        assert!(overlaps("ctrl+k", "ctrl+k ctrl+s"));
+        assert!(overlaps("g i", "g"));
+        assert!(overlaps("altgraph+e", "e+altgraph"));
+        assert!(overlaps("capslock+a", "a"));
+
+        assert!(!overlaps("ctrl+k", "ctrl+shift+k"));
+        assert!(!overlaps("controlleft+k", "controlright+k"));
+        assert!(!overlaps("key:enter", "code:enter"));
+        assert!(!overlaps("g i", "g o"));
+        assert!(!overlaps("capslock+a", "!capslock+a"));
+    }
+
+    #[test]
+    fn lock_keys() {
+        let hotkey = Hotkey::new("capslock");
+        assert_eq!(hotkey.keys, vec!["capslock".to_string()]);
+        assert_eq!(hotkey.modifiers.caps_lock, None);
+
+        let hotkey = Hotkey::new("ctrl+numlock");
+        assert!(hotkey.modifiers.ctrl);
+        assert_eq!(hotkey.keys, vec!["numlock".to_string()]);
+        assert_eq!(hotkey.modifiers.num_lock, None);
+        assert_eq!(hotkey.to_string().parse::<Hotkey>(), Ok(hotkey));
+
+        let hotkey = Hotkey::new("capslock+fn");
+        assert_eq!(hotkey.keys, vec!["fn".to_string()]);
+        assert_eq!(hotkey.modifiers.caps_lock, Some(true));
+
+        let hotkey = Hotkey::new("capslock+a");
+        assert_eq!(hotkey.keys, vec!["a".to_string()]);
+        assert_eq!(hotkey.modifiers.caps_lock, Some(true));
+
+        assert_eq!(
+            "!capslock".parse::<Hotkey>(),
+            Err(HotkeyParseError::ModifierOnly { span: 0..9 })
+        );
+        assert_eq!(
+            crate::__private::hotkeys!(crate, "capslock,ctrl+numlock"),
+            parse_key_combination("capslock,ctrl+numlock").unwrap()
+        );
+    }
+
+    #[test]
+    fn key_mode_prefixes() {
+        let hotkey = Hotkey::new("shift+Key:?");
+        assert_eq!(hotkey.keys, vec!["key:?".to_string()]);
+        assert_eq!(hotkey.to_string().parse::<Hotkey>(), Ok(hotkey));
+
+        let hotkey = Hotkey::new("code:KeyW+code:Space");
+        assert_eq!(
+            hotkey.keys,
+            vec!["code:keyw".to_string(), "code:spacebar".to_string()]
+        );
+        assert_ne!(Hotkey::new("keyw"), Hotkey::new("code:keyw"));
+
+        assert_eq!(
+            "ctrl+code:ctrl".parse::<Hotkey>(),
+            Err(HotkeyParseError::UnknownKey {
+                key: "code:ctrl".to_string(),
+                span: 5..14,
+            })
+        );
+        for input in [
+            "code:?",
+            "code:a",
+            "key:keyw",
+            "key:digit1",
+            "key:slash",
+            "key:numpadadd",
+        ] {
+            assert_eq!(
+                input.parse::<Hotkey>(),
+                Err(HotkeyParseError::UnknownKey {
+                    key: input.to_string(),
+                    span: 0..input.len(),
+                }),
+            );
+        }
+        assert_eq!(
+            crate::__private::hotkeys!(crate, "key:?,code:slash"),
+            parse_key_combination("key:?,code:slash").unwrap()
+        );
+    }
 }
@@ -0,0 +1,245 @@
+use crate::{Hotkey, Scope};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+
+/// Key combinations chosen by the user, by action name.
+pub type KeymapOverrides = HashMap<String, String>;
+
+/// A keymap document listing named actions and their hotkeys, to ship keymap
+/// files or back up the user's choices.
+///
+/// With the `serde` feature it (de)serializes as a map of action names, like
+/// this in TOML:
+///
+/// ```toml
+/// ["editor.save"]
+/// bindings = ["s+Mod"]
+/// scopes = ["editor"]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct Keymap {
+    pub actions: BTreeMap<String, KeymapEntry>,
+}
+
+/// An action of a [`Keymap`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeymapEntry {
+    /// Hotkeys triggering the action.
+    pub bindings: Vec<Hotkey>,
+    /// Scopes the action is registered in. Informative: importing a keymap
+    /// only rebinds the actions.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub scopes: Vec<Scope>,
+    /// Condition the action is registered with. Informative like `scopes`.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub when: Option<String>,
+}
+
+impl KeymapEntry {
+    /// The bindings as a key combination like `"ctrl+s,meta+s"`, or `None`
+    /// if there are none.
+    pub(crate) fn key_combination(&self) -> Option<String> {
+        let bindings = self
+            .bindings
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        (!bindings.is_empty()).then(|| bindings.join(","))
+    }
+}
+
+/// Where the user's keymap overrides are kept between visits.
+///
+/// Set it with [`HotkeysContext::set_keymap_storage`](crate::HotkeysContext::set_keymap_storage).
+pub trait KeymapStorage {
+    /// Overrides saved so far, empty if there are none.
+    fn load(&self) -> KeymapOverrides;
+    /// Replaces the saved overrides.
+    fn save(&self, overrides: &KeymapOverrides);
+}
+
+/// Keeps the overrides in memory, for tests or when they shouldn't persist.
+///
+/// Clones share the same overrides.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryKeymapStorage {
+    overrides: Rc<RefCell<KeymapOverrides>>,
+}
+
+impl MemoryKeymapStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A storage with some overrides already saved.
+    pub fn with_overrides(overrides: KeymapOverrides) -> Self {
+        MemoryKeymapStorage {
+            overrides: Rc::new(RefCell::new(overrides)),
+        }
+    }
+}
+
+impl KeymapStorage for MemoryKeymapStorage {
+    fn load(&self) -> KeymapOverrides {
+        self.overrides.borrow().clone()
+    }
+
+    fn save(&self, overrides: &KeymapOverrides) {
+        *self.overrides.borrow_mut() = overrides.clone();
+    }
+}
+
+/// Keeps the overrides in the browser's `localStorage`, as a JSON object
+/// under `key`. Nothing is loaded or saved when rendering on the server.
+#[derive(Debug, Clone)]
+pub struct LocalStorageKeymapStorage {
+    #[cfg_attr(feature = "ssr", allow(dead_code))]
+    key: String,
+}
+
+impl LocalStorageKeymapStorage {
+    pub fn new(key: impl Into<String>) -> Self {
+        LocalStorageKeymapStorage { key: key.into() }
+    }
+}
+
+impl Default for LocalStorageKeymapStorage {
+    fn default() -> Self {
+        Self::new("leptos_hotkeys.keymap")
+    }
+}
+
+#[cfg(not(feature = "ssr"))]
+impl LocalStorageKeymapStorage {
+    fn storage() -> Option<web_sys::Storage> {
+        leptos::window().local_storage().ok().flatten()
+    }
+}
+
+impl KeymapStorage for LocalStorageKeymapStorage {
+    fn load(&self) -> KeymapOverrides {
+        #[cfg(not(feature = "ssr"))]
+        {
+            use wasm_bindgen::JsCast;
+
+            let json =
+                Self::storage().and_then(|storage| storage.get_item(&self.key).ok().flatten());
+            // Anything but a JSON object, like `null` or a string, has no overrides.
+            let Some(object) = json
+                .and_then(|json| js_sys::JSON::parse(&json).ok())
+                .and_then(|value| value.dyn_into::<js_sys::Object>().ok())
+            else {
+                return KeymapOverrides::new();
+            };
+
+            js_sys::Object::entries(&object)
+                .iter()
+                .filter_map(|entry| {
+                    let entry = js_sys::Array::from(&entry);
+                    Some((entry.get(0).as_string()?, entry.get(1).as_string()?))
+                })
+                .collect()
+        }
+
+        #[cfg(feature = "ssr")]
+        KeymapOverrides::new()
+    }
+
+    #[cfg_attr(feature = "ssr", allow(unused_variables))]
+    fn save(&self, overrides: &KeymapOverrides) {
+        #[cfg(not(feature = "ssr"))]
+        {
+            let object = js_sys::Object::new();
+            for (action, key_combination) in overrides {
+                let _ = js_sys::Reflect::set(&object, &action.into(), &key_combination.into());
+            }
+
+            let json = js_sys::JSON::stringify(&object)
+                .ok()
+                .and_then(|json| json.as_string());
+            if let (Some(storage), Some(json)) = (Self::storage(), json) {
+                if storage.set_item(&self.key, &json).is_err() {
+                    leptos::logging::error!("failed to save the keymap to localStorage");
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keymap_entry_key_combination() {
+        let entry = KeymapEntry {
+            bindings: vec![Hotkey::new("ctrl+s"), Hotkey::new("g s")],
+            ..Default::default()
+        };
+        assert_eq!(entry.key_combination().as_deref(), Some("s+Ctrl,g s"));
+        assert_eq!(KeymapEntry::default().key_combination(), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn keymap_serde() {
+        let toml = r#"
+            ["editor.save"]
+            bindings = ["mod+s", "ctrl+k ctrl+s"]
+            scopes = ["editor"]
+
+            ["help.open"]
+            bindings = ["shift+key:?"]
+            when = "!inputFocused"
+        "#;
+        let keymap: Keymap = toml::from_str(toml).unwrap();
+        assert_eq!(
+            keymap.actions["editor.save"].bindings,
+            vec![Hotkey::new("mod+s"), Hotkey::new("ctrl+k ctrl+s")]
+        );
+        assert_eq!(
+            keymap.actions["help.open"].when.as_deref(),
+            Some("!inputFocused")
+        );
+
+        let json = serde_json::to_string(&keymap).unwrap();
+        assert!(json.starts_with(r#"{"editor.save":{"bindings":["s+Mod","k+Ctrl s+Ctrl"]"#));
+        assert_eq!(serde_json::from_str::<Keymap>(&json).unwrap(), keymap);
+        assert_eq!(
+            toml::from_str::<Keymap>(&toml::to_string(&keymap).unwrap()).unwrap(),
+            keymap
+        );
+
+        let err = serde_json::from_str::<Keymap>(r#"{"a":{"bindings":["ctlr+s"]}}"#).unwrap_err();
+        assert!(err.to_string().contains("unknown key \"ctlr\""), "{}", err);
+    }
+
+    #[test]
+    fn memory_storage_is_shared_by_clones() {
+        let storage = MemoryKeymapStorage::with_overrides(KeymapOverrides::from([(
+            "editor.save".to_string(),
+            "ctrl+s".to_string(),
+        )]));
+        let clone = storage.clone();
+
+        let mut overrides = clone.load();
+        overrides.insert("editor.open".to_string(), "ctrl+o".to_string());
+        clone.save(&overrides);
+
+        assert_eq!(storage.load(), overrides);
+        assert!(MemoryKeymapStorage::new().load().is_empty());
+    }
+}
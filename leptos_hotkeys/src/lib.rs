@@ -1,10 +1,53 @@
+// lets the derive macros refer to `::leptos_hotkeys` inside this crate
+extern crate self as leptos_hotkeys;
+
+mod cheat_sheet;
+mod command_palette;
 mod context;
+mod event;
+mod handle;
 mod hotkey;
+mod keymap;
 mod macros;
+#[cfg(not(feature = "ssr"))]
+mod matcher;
+mod options;
+mod registry;
+mod scope;
 mod types;
 mod use_hotkeys;
+mod use_scope;
+mod when;
 
+pub use cheat_sheet::HotkeysCheatSheet;
+pub use command_palette::CommandPalette;
 pub use context::{provide_hotkeys_context, use_hotkeys_context, HotkeysContext};
+pub use event::HotkeyEvent;
+pub use handle::HotkeyHandle;
 pub use hotkey::Hotkey;
-pub use types::KeyboardModifiers;
-pub use use_hotkeys::{use_hotkeys_ref, use_hotkeys_scoped};
+pub use keymap::{
+    Keymap, KeymapEntry, KeymapOverrides, KeymapStorage, LocalStorageKeymapStorage,
+    MemoryKeymapStorage,
+};
+pub use leptos_hotkeys_grammar::{HotkeyParseError, KeyMode, ModifierSide};
+pub use leptos_hotkeys_macros::HotkeyScope;
+pub use options::HotkeyOptions;
+pub use registry::{HotkeyConflict, RegisteredHotkey};
+pub use scope::{HotkeyScope, Scope, Scopes};
+pub use types::{KeyboardModifiers, Platform};
+pub use use_hotkeys::{
+    try_use_hotkeys_hold, try_use_hotkeys_ref, try_use_hotkeys_ref_with_options,
+    try_use_hotkeys_scoped, try_use_hotkeys_scoped_with_options, try_use_hotkeys_when,
+    use_hotkeys_hold, use_hotkeys_ref, use_hotkeys_ref_with_options, use_hotkeys_scoped,
+    use_hotkeys_scoped_with_options, use_hotkeys_when,
+};
+pub use use_scope::{use_focus_scope, use_scope, ActiveScope};
+pub use when::{HotkeyRegistrationError, WhenClause, WhenParseError};
+
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::hotkey::{hotkeys_from_static, StaticChord};
+    pub use crate::use_hotkeys::{use_parsed_hotkeys_ref, use_parsed_hotkeys_scoped};
+    pub use leptos_hotkeys_grammar::Modifier;
+    pub use leptos_hotkeys_macros::hotkeys;
+}
@@ -1,19 +1,25 @@
+/// Set of initially active [`Scope`](crate::Scope)s, always including the
+/// global `*` scope. Names are case-insensitive.
+///
+/// Scopes are anything implementing [`HotkeyScope`](crate::HotkeyScope), like
+/// strings or typed scopes. They can be dotted paths like `"editor.vim.insert"`, which also activate
+/// the bindings of `"editor.vim"`, `"editor"` and `"editor.*"`.
 #[macro_export]
 macro_rules! scopes {
     () => {
         {
-            let mut set = std::collections::HashSet::<String>::new();
-            set.insert("*".to_string());
+            let mut set = std::collections::HashSet::<$crate::Scope>::new();
+            set.insert($crate::Scope::GLOBAL);
             set
         }
     };
 
     ($($lit:literal),+ $(,)?) => {
         {
-            let mut temp_set = std::collections::HashSet::<String>::new();
-            temp_set.insert("*".to_string());
+            let mut temp_set = std::collections::HashSet::<$crate::Scope>::new();
+            temp_set.insert($crate::Scope::GLOBAL);
             $(
-                temp_set.insert($lit.to_string());
+                temp_set.insert($crate::HotkeyScope::into_scope($lit));
             )+
             temp_set
         }
@@ -21,141 +27,212 @@ macro_rules! scopes {
 
     ($($expr:expr),+ $(,)?) => {
         {
-            let mut temp_set = std::collections::HashSet::<String>::new();
-            temp_set.insert("*".to_string());
+            let mut temp_set = std::collections::HashSet::<$crate::Scope>::new();
+            temp_set.insert($crate::Scope::GLOBAL);
             $(
-                temp_set.insert($expr);
+                temp_set.insert($crate::HotkeyScope::into_scope($expr));
             )+
             temp_set
         }
     };
 }
 
+/// Registers a callback for a key combination, in the global `*` scope or in
+/// the given scopes, until the current reactive owner is cleaned up.
+///
+/// Literal key combinations are checked when compiling:
+///
+/// ```no_run
+/// use leptos_hotkeys::use_hotkeys;
+///
+/// use_hotkeys!(("ctrl+s") => move |_| ());
+/// use_hotkeys!(("escape", scopes = ["modal"]) => move |_| ());
+/// ```
+///
+/// so a misspelled key fails to compile,
+///
+/// ```compile_fail
+/// use leptos_hotkeys::use_hotkeys;
+///
+/// use_hotkeys!(("ctlr+s") => move |_| ());
+/// ```
+///
+/// as does a chord of modifiers only:
+///
+/// ```compile_fail
+/// use leptos_hotkeys::use_hotkeys;
+///
+/// use_hotkeys!(("shift+meta") => move |_| ());
+/// ```
 #[macro_export]
 macro_rules! use_hotkeys {
-    (($key_combo:literal) => $($code:tt)*) => {
+    (($key_combo:literal, scopes = $scopes:expr) => $($code:tt)*) => {
+        {
+            $crate::__private::use_parsed_hotkeys_scoped(
+                $crate::__private::hotkeys!($crate, $key_combo),
+                ::leptos::Callback::new(
+                    $($code)*
+                ),
+                $crate::Scopes::from($scopes),
+                $crate::HotkeyOptions::default(),
+            )
+        }
+    };
+
+    (($key_combo:expr, scopes = $scopes:expr) => $($code:tt)*) => {
         {
             $crate::use_hotkeys_scoped(
-                $key_combo.to_string(),
+                $key_combo,
                 ::leptos::Callback::new(
                     $($code)*
                 ),
-                vec!["*".to_string()]
-            );
+                $scopes
+            )
         }
     };
 
-    (($key_combo:expr) => $($code:tt)*) => {
+    (($key_combo:literal) => $($code:tt)*) => {
         {
-            use_hotkeys_scoped(
-                $key_combo.to_string(),
+            $crate::__private::use_parsed_hotkeys_scoped(
+                $crate::__private::hotkeys!($crate, $key_combo),
                 ::leptos::Callback::new(
                     $($code)*
                 ),
-                vec!["*".to_string()]
-            );
+                vec![$crate::Scope::GLOBAL],
+                $crate::HotkeyOptions::default(),
+            )
         }
     };
 
-    (($key_combo:expr $(, $scopes:literal)*) => $($code:tt)*) => {
+    (($key_combo:literal $(, $scopes:expr)*) => $($code:tt)*) => {
         {
-            $crate::use_hotkeys_scoped(
-                $key_combo.to_string(),
+            $crate::__private::use_parsed_hotkeys_scoped(
+                $crate::__private::hotkeys!($crate, $key_combo),
                 ::leptos::Callback::new(
                     $($code)*
                 ),
-                vec![$($scopes.to_string(),)*]
-            );
+                vec![$($crate::HotkeyScope::into_scope($scopes),)*],
+                $crate::HotkeyOptions::default(),
+            )
         }
     };
 
-    (($key_combo:literal $(, $scopes:literal)*) => $($code:tt)*) => {
+    (($key_combo:expr) => $($code:tt)*) => {
         {
             $crate::use_hotkeys_scoped(
-                $key_combo.to_string(),
+                $key_combo,
                 ::leptos::Callback::new(
                     $($code)*
                 ),
-                vec![$($scopes.to_string(),)*]
-            );
+                vec![$crate::Scope::GLOBAL]
+            )
         }
     };
 
-    (($key_combo:literal $(, $scopes:expr)*) => $($code:tt)*) => {
+    (($key_combo:expr $(, $scopes:literal)*) => $($code:tt)*) => {
         {
             $crate::use_hotkeys_scoped(
-                $key_combo.to_string(),
+                $key_combo,
                 ::leptos::Callback::new(
                     $($code)*
                 ),
-                vec![$($scopes.to_string(),)*]
-            );
+                vec![$($crate::HotkeyScope::into_scope($scopes),)*]
+            )
         }
     };
 
     (($key_combo:expr $(, $scopes:expr)*) => $($code:tt)*) => {
         {
             $crate::use_hotkeys_scoped(
-                $key_combo.to_string(),
+                $key_combo,
                 ::leptos::Callback::new(
                     $($code)*
                 ),
-                vec![$($scopes.to_string(),)*]
-            );
+                vec![$($crate::HotkeyScope::into_scope($scopes),)*]
+            )
         }
     };
 }
 
 #[macro_export]
 macro_rules! use_hotkeys_ref {
-    (($node_ref:expr, $key_combo:literal) => $($code:tt)*) => {
+    (($node_ref:expr, $key_combo:literal, scopes = $scopes:expr) => $($code:tt)*) => {
         {
-            $crate::use_hotkeys_ref(
+            $crate::__private::use_parsed_hotkeys_ref(
                 $node_ref,
-                $key_combo.to_string(),
+                $crate::__private::hotkeys!($crate, $key_combo),
                 ::leptos::Callback::new(
                     $($code)*
                 ),
-                vec!["*".to_string()]
+                $crate::Scopes::from($scopes),
+                $crate::HotkeyOptions::default(),
             )
         }
     };
 
-    (($node_ref:expr, $key_combo:expr) => $($code:tt)*) => {
+    (($node_ref:expr, $key_combo:expr, scopes = $scopes:expr) => $($code:tt)*) => {
         {
             $crate::use_hotkeys_ref(
                 $node_ref,
-                $key_combo.to_string(),
+                $key_combo,
                 ::leptos::Callback::new(
                     $($code)*
                 ),
-                vec!["*".to_string()]
+                $scopes
             )
         }
     };
 
-    (($node_ref:expr, $key_combo:expr $(, $scopes:literal)*) => $($code:tt)*) => {
+    (($node_ref:expr, $key_combo:literal) => $($code:tt)*) => {
+        {
+            $crate::__private::use_parsed_hotkeys_ref(
+                $node_ref,
+                $crate::__private::hotkeys!($crate, $key_combo),
+                ::leptos::Callback::new(
+                    $($code)*
+                ),
+                vec![$crate::Scope::GLOBAL],
+                $crate::HotkeyOptions::default(),
+            )
+        }
+    };
+
+    (($node_ref:expr, $key_combo:literal $(, $scopes:expr)*) => $($code:tt)*) => {
+        {
+            $crate::__private::use_parsed_hotkeys_ref(
+                $node_ref,
+                $crate::__private::hotkeys!($crate, $key_combo),
+                ::leptos::Callback::new(
+                    $($code)*
+                ),
+                vec![$($crate::HotkeyScope::into_scope($scopes),)*],
+                $crate::HotkeyOptions::default(),
+            )
+        }
+    };
+
+    (($node_ref:expr, $key_combo:expr) => $($code:tt)*) => {
         {
             $crate::use_hotkeys_ref(
                 $node_ref,
-                $key_combo.to_string(),
+                $key_combo,
                 ::leptos::Callback::new(
                     $($code)*
                 ),
-                vec![$($scopes.to_string(),)*]
+                vec![$crate::Scope::GLOBAL]
             )
         }
     };
 
-    (($node_ref:expr, $key_combo:literal $(, $scopes:literal)*) => $($code:tt)*) => {
+    (($node_ref:expr, $key_combo:expr $(, $scopes:literal)*) => $($code:tt)*) => {
         {
             $crate::use_hotkeys_ref(
                 $node_ref,
-                $key_combo.to_string(),
+                $key_combo,
                 ::leptos::Callback::new(
                     $($code)*
                 ),
-                vec![$($scopes.to_string(),)*]
+                vec![$($crate::HotkeyScope::into_scope($scopes),)*]
             )
         }
     };
@@ -163,11 +240,11 @@ macro_rules! use_hotkeys_ref {
     (($key_combo:literal $(, $scopes:expr)*) => $($code:tt)*) => {
         {
             $crate::use_hotkeys_ref(
-                $key_combo.to_string(),
+                $key_combo,
                 ::leptos::Callback::new(
                     $($code)*
                 ),
-                vec![$($scopes.to_string(),)*]
+                vec![$($crate::HotkeyScope::into_scope($scopes),)*]
             )
         }
     };
@@ -175,11 +252,11 @@ macro_rules! use_hotkeys_ref {
     (($key_combo:expr $(, $scopes:expr)*) => $($code:tt)*) => {
         {
             $crate::use_hotkeys_ref(
-                $key_combo.to_string(),
+                $key_combo,
                 ::leptos::Callback::new(
                     $($code)*
                 ),
-                vec![$($scopes.to_string(),)*]
+                vec![$($crate::HotkeyScope::into_scope($scopes),)*]
             )
         }
     };
@@ -0,0 +1,265 @@
+use crate::context::KeyPresses;
+use crate::hotkey::{
+    is_hotkey_match, is_last_key_match, is_modifier_event, SequenceState, SequenceStep,
+};
+use crate::registry::is_shadowed;
+use crate::types::event_value;
+use crate::{Hotkey, HotkeyEvent, HotkeyOptions, HotkeysContext, KeyMode, Platform};
+use leptos::leptos_dom::helpers::TimeoutHandle;
+use leptos::*;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+/// Matching state of the hotkeys of a single binding.
+#[derive(Clone, Copy)]
+pub(crate) struct Matcher {
+    id: usize,
+    hotkeys: StoredValue<HashSet<Hotkey>>,
+    sequences: StoredValue<HashMap<Hotkey, SequenceState>>,
+    reset_handle: StoredValue<Option<TimeoutHandle>>,
+    /// Hotkey matched on keydown, until it is released.
+    held: StoredValue<Option<Hotkey>>,
+    sequence_timeout: Option<Duration>,
+    key_mode: Option<KeyMode>,
+    prevent_default: bool,
+    stop_propagation: bool,
+    keydown: bool,
+    keyup: bool,
+    allow_repeat: bool,
+}
+
+impl Matcher {
+    pub(crate) fn new(
+        hotkeys_context: &HotkeysContext,
+        id: usize,
+        hotkeys: HashSet<Hotkey>,
+        options: &HotkeyOptions,
+    ) -> Self {
+        let matcher = Matcher {
+            id,
+            hotkeys: StoredValue::new(hotkeys),
+            sequences: StoredValue::new(Default::default()),
+            reset_handle: StoredValue::new(None),
+            held: StoredValue::new(None),
+            sequence_timeout: options.sequence_timeout,
+            key_mode: options.key_mode,
+            prevent_default: options.prevent_default.unwrap_or(true),
+            stop_propagation: options.stop_propagation,
+            keydown: options.keydown.unwrap_or(true),
+            keyup: options.keyup,
+            allow_repeat: options.allow_repeat.unwrap_or(true),
+        };
+
+        let hotkeys_context = *hotkeys_context;
+        on_cleanup(move || matcher.reset(&hotkeys_context));
+
+        matcher
+    }
+
+    /// Event to fire the binding with for the last key press, if it completes
+    /// a hotkey and the binding fires on keydown.
+    pub(crate) fn find_match(
+        &self,
+        hotkeys_context: &HotkeysContext,
+        keys_pressed: &KeyPresses,
+    ) -> Option<HotkeyEvent> {
+        let event = keys_pressed.last_event()?;
+        if event.repeat() && !self.allow_repeat {
+            return None;
+        }
+
+        let hotkey = self.match_hotkey(hotkeys_context, keys_pressed)?;
+        if self.keyup {
+            self.held.set_value(Some(hotkey.clone()));
+        }
+        if !self.keydown {
+            return None;
+        }
+        self.apply_to(event);
+        Some(HotkeyEvent::new(hotkey, Some(event.clone())))
+    }
+
+    /// Event to fire the binding with for the release `event`, if it ends the
+    /// held hotkey and the binding fires on keyup.
+    pub(crate) fn find_release(
+        &self,
+        hotkeys_context: &HotkeysContext,
+        event: &web_sys::KeyboardEvent,
+    ) -> Option<HotkeyEvent> {
+        let key_mode = self
+            .key_mode
+            .unwrap_or_else(|| hotkeys_context.key_mode.get_untracked());
+        let platform = hotkeys_context.platform.get_untracked();
+        let (key, code) = (
+            event_value(event, KeyMode::Key),
+            event_value(event, KeyMode::Code),
+        );
+        let ends_hotkey =
+            |hotkey: &mut Hotkey| hotkey.is_released_by(&key, &code, key_mode, platform);
+        let hotkey = self
+            .held
+            .try_update_value(|held| held.take_if(ends_hotkey))
+            .flatten()?;
+
+        self.apply_to(event);
+        Some(HotkeyEvent::new(hotkey, Some(event.clone())))
+    }
+
+    /// Event to fire the binding with for the hotkey held down, without a key
+    /// release, when the binding stops running or the window loses focus.
+    pub(crate) fn release_held(&self) -> Option<HotkeyEvent> {
+        let hotkey = self.held.try_update_value(Option::take).flatten()?;
+        Some(HotkeyEvent::synthetic_release(hotkey))
+    }
+
+    /// Holds `hotkey` down as if it was pressed, for tests.
+    #[cfg(test)]
+    pub(crate) fn hold(&self, hotkey: Hotkey) {
+        self.held.set_value(Some(hotkey));
+    }
+
+    /// Calls `preventDefault()` and `stopPropagation()` on the event the
+    /// binding fires for, as set in its options.
+    fn apply_to(&self, event: &web_sys::KeyboardEvent) {
+        if self.prevent_default {
+            event.prevent_default();
+        }
+        if self.stop_propagation {
+            event.stop_propagation();
+        }
+    }
+
+    /// Hotkey completed by the last key press, if any.
+    fn match_hotkey(
+        &self,
+        hotkeys_context: &HotkeysContext,
+        keys_pressed: &KeyPresses,
+    ) -> Option<Hotkey> {
+        let platform = hotkeys_context.platform.get_untracked();
+        let key_mode = self
+            .key_mode
+            .unwrap_or_else(|| hotkeys_context.key_mode.get_untracked());
+        self.hotkeys.with_value(|hotkeys| {
+            let chords = hotkeys.iter().filter(|hotkey| !hotkey.is_sequence());
+            let matching_chord = if is_last_key_match(chords.clone(), keys_pressed, key_mode) {
+                chords
+                    .clone()
+                    .find(|hotkey| is_hotkey_match(hotkey, keys_pressed, platform, key_mode))
+                    .cloned()
+            } else {
+                None
+            };
+
+            let completed_sequence =
+                self.advance_sequences(hotkeys_context, hotkeys, keys_pressed, platform, key_mode);
+            matching_chord
+                .or(completed_sequence)
+                .filter(|hotkey| !self.is_shadowed(hotkeys_context, hotkey, platform))
+        })
+    }
+
+    /// Whether a binding with a higher priority fires for `hotkey` instead.
+    fn is_shadowed(
+        &self,
+        hotkeys_context: &HotkeysContext,
+        hotkey: &Hotkey,
+        platform: Platform,
+    ) -> bool {
+        untrack(|| {
+            hotkeys_context.bindings.with(|bindings| {
+                hotkeys_context.with_conditions(|conditions| {
+                    is_shadowed(bindings, self.id, hotkey, conditions, platform)
+                })
+            })
+        })
+    }
+
+    fn advance_sequences(
+        &self,
+        hotkeys_context: &HotkeysContext,
+        hotkeys: &HashSet<Hotkey>,
+        keys_pressed: &KeyPresses,
+        platform: Platform,
+        key_mode: KeyMode,
+    ) -> Option<Hotkey> {
+        let event = keys_pressed.last_event()?;
+        // modifiers are pressed on their own before the rest of a chord
+        if is_modifier_event(event) || event.repeat() {
+            return None;
+        }
+        let now = event.time_stamp();
+        let timeout = self
+            .sequence_timeout
+            .unwrap_or_else(|| hotkeys_context.sequence_timeout.get_untracked());
+
+        let mut completed = None;
+        let mut progress: &[Hotkey] = &[];
+        self.sequences.update_value(|states| {
+            for hotkey in hotkeys.iter().filter(|hotkey| hotkey.is_sequence()) {
+                let state = states.entry(hotkey.clone()).or_default();
+                let step = state.advance(hotkey, now, timeout.as_millis() as f64, |chord| {
+                    chord.includes_key(event, key_mode)
+                        && is_hotkey_match(chord, keys_pressed, platform, key_mode)
+                });
+
+                if step == SequenceStep::Completed && completed.is_none() {
+                    completed = Some(hotkey.clone());
+                }
+                if state.matched() > progress.len() {
+                    progress = &hotkey.prefix[..state.matched()];
+                }
+            }
+        });
+
+        self.clear_reset_timeout();
+        if progress.is_empty() {
+            self.clear_progress(hotkeys_context);
+        } else {
+            hotkeys_context.sequence_progress.update(|sequences| {
+                sequences.insert(self.id, progress.to_vec());
+            });
+
+            let matcher = *self;
+            let hotkeys_context = *hotkeys_context;
+            let handle = set_timeout_with_handle(move || matcher.reset(&hotkeys_context), timeout);
+            self.reset_handle.set_value(handle.ok());
+        }
+
+        completed
+    }
+
+    /// Replaces the hotkeys, when the user rebinds the action for example.
+    pub(crate) fn set_hotkeys(&self, hotkeys_context: &HotkeysContext, hotkeys: HashSet<Hotkey>) {
+        self.reset(hotkeys_context);
+        self.sequences.update_value(|states| states.clear());
+        self.held.set_value(None);
+        self.hotkeys.set_value(hotkeys);
+    }
+
+    /// Drops the progress of every sequence.
+    fn reset(&self, hotkeys_context: &HotkeysContext) {
+        self.clear_reset_timeout();
+        self.sequences.try_update_value(|states| {
+            states.values_mut().for_each(|state| state.reset());
+        });
+        self.clear_progress(hotkeys_context);
+    }
+
+    fn clear_reset_timeout(&self) {
+        // the stored values may be disposed already when cleaning up
+        if let Some(Some(handle)) = self.reset_handle.try_update_value(Option::take) {
+            handle.clear();
+        }
+    }
+
+    fn clear_progress(&self, hotkeys_context: &HotkeysContext) {
+        let in_progress = hotkeys_context
+            .sequence_progress
+            .with_untracked(|sequences| sequences.contains_key(&self.id));
+        if in_progress {
+            hotkeys_context.sequence_progress.update(|sequences| {
+                sequences.remove(&self.id);
+            });
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use crate::{KeyMode, WhenClause};
+use leptos::Signal;
+use std::time::Duration;
+
+/// Per-binding settings for [`use_hotkeys_scoped_with_options`] and
+/// [`use_hotkeys_ref_with_options`].
+///
+/// [`use_hotkeys_scoped_with_options`]: crate::use_hotkeys_scoped_with_options
+/// [`use_hotkeys_ref_with_options`]: crate::use_hotkeys_ref_with_options
+#[derive(Debug, Clone, Default)]
+pub struct HotkeyOptions {
+    pub(crate) sequence_timeout: Option<Duration>,
+    pub(crate) key_mode: Option<KeyMode>,
+    pub(crate) priority: Option<i32>,
+    pub(crate) description: Option<String>,
+    pub(crate) group: Option<String>,
+    pub(crate) action: Option<String>,
+    pub(crate) when: Option<WhenClause>,
+    pub(crate) prevent_default: Option<bool>,
+    pub(crate) stop_propagation: bool,
+    pub(crate) enabled: Option<Signal<bool>>,
+    pub(crate) keydown: Option<bool>,
+    pub(crate) keyup: bool,
+    pub(crate) allow_repeat: Option<bool>,
+}
+
+impl HotkeyOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maximum delay between two chords of a sequence like `"g i"`.
+    ///
+    /// Defaults to [`HotkeysContext::sequence_timeout`](crate::HotkeysContext::sequence_timeout).
+    pub fn sequence_timeout(mut self, timeout: Duration) -> Self {
+        self.sequence_timeout = Some(timeout);
+        self
+    }
+
+    /// How key names without a `key:` or `code:` prefix are compared with key
+    /// presses.
+    ///
+    /// Defaults to [`HotkeysContext::key_mode`](crate::HotkeysContext::key_mode).
+    pub fn key_mode(mut self, key_mode: KeyMode) -> Self {
+        self.key_mode = Some(key_mode);
+        self
+    }
+
+    /// Precedence over other bindings of the same hotkey in active scopes:
+    /// only the bindings with the highest priority fire, and bindings without
+    /// a priority come last.
+    ///
+    /// Bindings of the same hotkey and priority are reported by
+    /// [`HotkeysContext::conflicts`](crate::HotkeysContext::conflicts).
+    pub fn priority(mut self, priority: i32) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// Human readable description of what the binding does, listed in
+    /// [`HotkeysContext::registry`](crate::HotkeysContext::registry).
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Category the binding is listed under in
+    /// [`HotkeysContext::registry`](crate::HotkeysContext::registry), like
+    /// `"Navigation"`.
+    pub fn group(mut self, group: impl Into<String>) -> Self {
+        self.group = Some(group.into());
+        self
+    }
+
+    /// Names the action the binding performs, like `"editor.save"`, so users
+    /// can rebind it with [`HotkeysContext::rebind`](crate::HotkeysContext::rebind).
+    /// The key combination of the binding is then only the default.
+    pub fn action(mut self, action: impl Into<String>) -> Self {
+        self.action = Some(action.into());
+        self
+    }
+
+    /// Only fires while `when` holds, in addition to one of the scopes being
+    /// active. Bindings registered without scopes, like with
+    /// [`use_hotkeys_when`](crate::use_hotkeys_when), only depend on it.
+    pub fn when(mut self, when: WhenClause) -> Self {
+        self.when = Some(when);
+        self
+    }
+
+    /// Whether to call `preventDefault()` on the key event the binding fires
+    /// for, to override browser shortcuts like `ctrl+s`. Defaults to `true`.
+    pub fn prevent_default(mut self, prevent_default: bool) -> Self {
+        self.prevent_default = Some(prevent_default);
+        self
+    }
+
+    /// Whether to call `stopPropagation()` on the key event the binding fires
+    /// for. Defaults to `false`.
+    ///
+    /// Hotkeys are matched when the event bubbles up to the document, so this
+    /// only keeps it from listeners on `window`, and for bindings of
+    /// [`use_hotkeys_ref`](crate::use_hotkeys_ref) from the ancestors of the
+    /// element and from the hotkeys of the document.
+    pub fn stop_propagation(mut self, stop_propagation: bool) -> Self {
+        self.stop_propagation = stop_propagation;
+        self
+    }
+
+    /// Only fires while `enabled` is `true`. Unlike
+    /// [`HotkeyHandle::pause`](crate::HotkeyHandle::pause), it follows a signal.
+    pub fn enabled(mut self, enabled: impl Into<Signal<bool>>) -> Self {
+        self.enabled = Some(enabled.into());
+        self
+    }
+
+    /// Whether the binding fires when its hotkey is pressed. Defaults to `true`.
+    pub fn on_keydown(mut self, keydown: bool) -> Self {
+        self.keydown = Some(keydown);
+        self
+    }
+
+    /// Whether the binding fires when its hotkey is released, after being
+    /// pressed. Defaults to `false`.
+    pub fn on_keyup(mut self, keyup: bool) -> Self {
+        self.keyup = keyup;
+        self
+    }
+
+    /// Whether the binding fires again while its hotkey is held down and the
+    /// key press repeats. Defaults to `true`. Sequences never fire on repeats.
+    pub fn allow_repeat(mut self, allow_repeat: bool) -> Self {
+        self.allow_repeat = Some(allow_repeat);
+        self
+    }
+}
@@ -0,0 +1,382 @@
+use crate::scope::is_scope_active;
+use crate::{Hotkey, HotkeyEvent, HotkeyOptions, Platform, Scope, WhenClause};
+use leptos::{Callback, Signal, SignalGet};
+use std::collections::{BTreeMap, HashSet};
+
+/// A binding registered with the `use_hotkeys*` macros and functions.
+#[derive(Debug, Clone)]
+pub(crate) struct Binding {
+    pub(crate) hotkeys: HashSet<Hotkey>,
+    pub(crate) scopes: Vec<Scope>,
+    pub(crate) when: Option<WhenClause>,
+    pub(crate) priority: Option<i32>,
+    pub(crate) description: Option<String>,
+    pub(crate) group: Option<String>,
+    pub(crate) action: Option<String>,
+    /// Registered with `use_hotkeys_ref`, so it only fires in its element.
+    pub(crate) focus_trapped: bool,
+    /// Paused through its [`HotkeyHandle`](crate::HotkeyHandle).
+    pub(crate) paused: bool,
+    pub(crate) enabled: Option<Signal<bool>>,
+    pub(crate) on_triggered: Callback<HotkeyEvent>,
+}
+
+impl Binding {
+    pub(crate) fn new(
+        hotkeys: &HashSet<Hotkey>,
+        scopes: &[Scope],
+        on_triggered: Callback<HotkeyEvent>,
+        options: &HotkeyOptions,
+        focus_trapped: bool,
+    ) -> Self {
+        Binding {
+            hotkeys: hotkeys.clone(),
+            scopes: scopes.to_vec(),
+            when: options.when.clone(),
+            priority: options.priority,
+            description: options.description.clone(),
+            group: options.group.clone(),
+            action: options.action.clone(),
+            focus_trapped,
+            paused: false,
+            enabled: options.enabled,
+            on_triggered,
+        }
+    }
+
+    /// Whether the binding is neither paused nor disabled with
+    /// [`HotkeyOptions::enabled`].
+    pub(crate) fn is_enabled(&self) -> bool {
+        !self.paused && self.enabled.is_none_or(|enabled| enabled.get())
+    }
+
+    fn is_active(&self, conditions: &Conditions) -> bool {
+        self.is_enabled() && conditions.allow(&self.scopes, self.when.as_ref())
+    }
+
+    fn resolved_hotkeys(&self, platform: Platform) -> HashSet<Hotkey> {
+        self.hotkeys
+            .iter()
+            .map(|hotkey| hotkey.resolve(platform))
+            .collect()
+    }
+}
+
+/// What bindings are gated by: the active scopes, and the context keys set to
+/// `true` for their when clauses.
+#[derive(Clone, Copy)]
+pub(crate) struct Conditions<'a> {
+    pub(crate) active_scopes: &'a HashSet<Scope>,
+    pub(crate) context_keys: &'a HashSet<String>,
+}
+
+impl Conditions<'_> {
+    /// Whether a binding in `scopes` gated by `when` can fire. A binding
+    /// without scopes only depends on its when clause.
+    pub(crate) fn allow(&self, scopes: &[Scope], when: Option<&WhenClause>) -> bool {
+        let within_scope = match (scopes.is_empty(), when) {
+            (true, Some(_)) => true,
+            _ => scopes
+                .iter()
+                .any(|scope| is_scope_active(scope, self.active_scopes)),
+        };
+        within_scope && when.is_none_or(|when| when.evaluate(self.active_scopes, self.context_keys))
+    }
+}
+
+/// A binding as listed by [`HotkeysContext::registry`](crate::HotkeysContext::registry).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegisteredHotkey {
+    /// Identifier of the binding, to run it with
+    /// [`HotkeysContext::trigger`](crate::HotkeysContext::trigger).
+    pub id: usize,
+    /// The hotkeys triggering the binding, sorted.
+    pub hotkeys: Vec<Hotkey>,
+    pub scopes: Vec<Scope>,
+    /// Set with [`HotkeyOptions::when`](crate::HotkeyOptions::when).
+    pub when: Option<WhenClause>,
+    /// Set with [`HotkeyOptions::description`](crate::HotkeyOptions::description).
+    pub description: Option<String>,
+    /// Set with [`HotkeyOptions::group`](crate::HotkeyOptions::group).
+    pub group: Option<String>,
+    /// Set with [`HotkeyOptions::action`](crate::HotkeyOptions::action).
+    pub action: Option<String>,
+    /// Whether the binding isn't paused, one of its scopes is active and its
+    /// when clause holds.
+    pub active: bool,
+    /// Whether the binding only fires in an element, as with `use_hotkeys_ref`.
+    pub focus_trapped: bool,
+}
+
+impl RegisteredHotkey {
+    pub(crate) fn new(id: usize, binding: &Binding, conditions: &Conditions) -> Self {
+        let mut hotkeys = binding.hotkeys.iter().cloned().collect::<Vec<_>>();
+        hotkeys.sort_by_key(|hotkey| hotkey.to_string());
+
+        RegisteredHotkey {
+            id,
+            hotkeys,
+            scopes: binding.scopes.clone(),
+            when: binding.when.clone(),
+            description: binding.description.clone(),
+            group: binding.group.clone(),
+            action: binding.action.clone(),
+            active: binding.is_active(conditions),
+            focus_trapped: binding.focus_trapped,
+        }
+    }
+}
+
+/// Two active bindings triggered by the same key presses, neither of which
+/// takes precedence with [`HotkeyOptions::priority`](crate::HotkeyOptions::priority).
+///
+/// Their hotkeys overlap when they are the same, like `ctrl+k` and
+/// `controlleft+k`, or when one is a sequence starting with the other, like
+/// `ctrl+k` and `ctrl+k ctrl+s`. Keys named in different modes, like `key:a`
+/// and `code:keya`, are not compared, as that depends on the keyboard layout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HotkeyConflict {
+    /// Hotkeys of each of the two bindings, with `mod` resolved for the
+    /// current platform.
+    pub hotkeys: [Hotkey; 2],
+    /// Scopes of each of the two bindings.
+    pub scopes: [Vec<Scope>; 2],
+}
+
+/// Conflicts between the active `bindings`. Focus trapped bindings never
+/// conflict, as they only fire in their own element.
+pub(crate) fn find_conflicts(
+    bindings: &BTreeMap<usize, Binding>,
+    conditions: &Conditions,
+    platform: Platform,
+) -> Vec<HotkeyConflict> {
+    let active = bindings
+        .values()
+        .filter(|binding| !binding.focus_trapped && binding.is_active(conditions))
+        .map(|binding| (binding, binding.resolved_hotkeys(platform)))
+        .collect::<Vec<_>>();
+
+    let mut conflicts = Vec::new();
+    for (i, (binding, hotkeys)) in active.iter().enumerate() {
+        for (other, other_hotkeys) in &active[i + 1..] {
+            if binding.priority != other.priority {
+                continue;
+            }
+
+            let mut overlapping = hotkeys
+                .iter()
+                .flat_map(|hotkey| {
+                    other_hotkeys
+                        .iter()
+                        .filter(|other_hotkey| hotkey.overlaps(other_hotkey))
+                        .map(move |other_hotkey| [hotkey.clone(), other_hotkey.clone()])
+                })
+                .collect::<Vec<_>>();
+            overlapping.sort_by_key(|hotkeys| hotkeys.each_ref().map(ToString::to_string));
+            conflicts.extend(overlapping.into_iter().map(|hotkeys| HotkeyConflict {
+                hotkeys,
+                scopes: [binding.scopes.clone(), other.scopes.clone()],
+            }));
+        }
+    }
+    conflicts
+}
+
+/// Whether an active binding other than `id` with a hotkey overlapping
+/// `hotkey` has a higher priority, in which case only that one fires.
+#[cfg_attr(feature = "ssr", allow(dead_code))]
+pub(crate) fn is_shadowed(
+    bindings: &BTreeMap<usize, Binding>,
+    id: usize,
+    hotkey: &Hotkey,
+    conditions: &Conditions,
+    platform: Platform,
+) -> bool {
+    let Some(binding) = bindings.get(&id) else {
+        return false;
+    };
+    let hotkey = hotkey.resolve(platform);
+
+    bindings.iter().any(|(other_id, other)| {
+        *other_id != id
+            && !other.focus_trapped
+            && other.priority > binding.priority
+            && other.is_active(conditions)
+            && other
+                .resolved_hotkeys(platform)
+                .iter()
+                .any(|other_hotkey| other_hotkey.overlaps(&hotkey))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hotkey::parse_key_combination;
+    use leptos::SignalSet;
+
+    fn binding(hotkeys: &str, scopes: &[&str], priority: Option<i32>) -> Binding {
+        Binding {
+            hotkeys: parse_key_combination(hotkeys).unwrap(),
+            scopes: scopes.iter().map(|scope| Scope::from(*scope)).collect(),
+            when: None,
+            priority,
+            description: None,
+            group: None,
+            action: None,
+            focus_trapped: false,
+            paused: false,
+            enabled: None,
+            on_triggered: Callback::new(|_| ()),
+        }
+    }
+
+    #[test]
+    fn conflicts_and_priorities() {
+        let runtime = leptos::create_runtime();
+        let active_scopes = HashSet::from([Scope::GLOBAL, Scope::from("editor")]);
+        let context_keys = HashSet::new();
+        let active_scopes = Conditions {
+            active_scopes: &active_scopes,
+            context_keys: &context_keys,
+        };
+        let mut bindings = BTreeMap::from([
+            (0, binding("ctrl+k,ctrl+p", &["*"], None)),
+            (1, binding("mod+k", &["editor"], None)),
+            (2, binding("ctrl+k", &["modal"], None)),
+        ]);
+
+        let conflicts = find_conflicts(&bindings, &active_scopes, Platform::Other);
+        assert_eq!(
+            conflicts,
+            vec![HotkeyConflict {
+                hotkeys: [Hotkey::new("ctrl+k"), Hotkey::new("ctrl+k")],
+                scopes: [vec![Scope::GLOBAL], vec![Scope::from("editor")]],
+            }]
+        );
+        assert!(find_conflicts(&bindings, &active_scopes, Platform::Apple).is_empty());
+
+        bindings.get_mut(&1).unwrap().priority = Some(1);
+        assert!(find_conflicts(&bindings, &active_scopes, Platform::Other).is_empty());
+
+        let hotkey = Hotkey::new("ctrl+k");
+        assert!(is_shadowed(
+            &bindings,
+            0,
+            &hotkey,
+            &active_scopes,
+            Platform::Other
+        ));
+        assert!(!is_shadowed(
+            &bindings,
+            1,
+            &hotkey,
+            &active_scopes,
+            Platform::Other
+        ));
+        assert!(!is_shadowed(
+            &bindings,
+            0,
+            &Hotkey::new("ctrl+p"),
+            &active_scopes,
+            Platform::Other
+        ));
+
+        runtime.dispose();
+    }
+
+    #[test]
+    fn overlapping_conflicts() {
+        let runtime = leptos::create_runtime();
+        let active_scopes = HashSet::from([Scope::GLOBAL]);
+        let context_keys = HashSet::new();
+        let conditions = Conditions {
+            active_scopes: &active_scopes,
+            context_keys: &context_keys,
+        };
+        let mut bindings = BTreeMap::from([
+            (0, binding("ctrl+k", &["*"], None)),
+            (1, binding("controlleft+k", &["*"], None)),
+            (2, binding("ctrl+k ctrl+s", &["*"], None)),
+        ]);
+
+        let conflicts = find_conflicts(&bindings, &conditions, Platform::Other);
+        let hotkeys = conflicts
+            .into_iter()
+            .map(|conflict| conflict.hotkeys)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            hotkeys,
+            vec![
+                [Hotkey::new("ctrl+k"), Hotkey::new("controlleft+k")],
+                [Hotkey::new("ctrl+k"), Hotkey::new("ctrl+k ctrl+s")],
+                [Hotkey::new("controlleft+k"), Hotkey::new("ctrl+k ctrl+s")],
+            ]
+        );
+
+        bindings.get_mut(&0).unwrap().priority = Some(1);
+        assert_eq!(
+            find_conflicts(&bindings, &conditions, Platform::Other).len(),
+            1
+        );
+        assert!(is_shadowed(
+            &bindings,
+            1,
+            &Hotkey::new("controlleft+k"),
+            &conditions,
+            Platform::Other
+        ));
+        assert!(is_shadowed(
+            &bindings,
+            2,
+            &Hotkey::new("ctrl+k ctrl+s"),
+            &conditions,
+            Platform::Other
+        ));
+
+        runtime.dispose();
+    }
+
+    #[test]
+    fn registered_hotkey() {
+        let runtime = leptos::create_runtime();
+        let mut binding = binding("shift+?,ctrl+h", &["*", "help"], None);
+        binding.description = Some("Open help".to_string());
+
+        let active_scopes = HashSet::from([Scope::from("help")]);
+        let no_scopes = HashSet::new();
+        let no_context_keys = HashSet::new();
+        let conditions = |active_scopes| Conditions {
+            active_scopes,
+            context_keys: &no_context_keys,
+        };
+        let registered = RegisteredHotkey::new(0, &binding, &conditions(&active_scopes));
+        assert_eq!(
+            registered.hotkeys,
+            vec![Hotkey::new("shift+?"), Hotkey::new("ctrl+h")]
+        );
+        assert_eq!(registered.description.as_deref(), Some("Open help"));
+        assert!(registered.active);
+        assert!(!RegisteredHotkey::new(0, &binding, &conditions(&no_scopes)).active);
+        binding.paused = true;
+        assert!(!RegisteredHotkey::new(0, &binding, &conditions(&active_scopes)).active);
+        binding.paused = false;
+        let enabled = leptos::create_rw_signal(false);
+        binding.enabled = Some(enabled.into());
+        assert!(!RegisteredHotkey::new(0, &binding, &conditions(&active_scopes)).active);
+        enabled.set(true);
+        assert!(RegisteredHotkey::new(0, &binding, &conditions(&active_scopes)).active);
+
+        binding.scopes.clear();
+        binding.when = Some("help && !modalOpen".parse().unwrap());
+        assert!(RegisteredHotkey::new(0, &binding, &conditions(&active_scopes)).active);
+        let modal_open = HashSet::from(["modalOpen".to_string()]);
+        let conditions = Conditions {
+            active_scopes: &active_scopes,
+            context_keys: &modal_open,
+        };
+        assert!(!RegisteredHotkey::new(0, &binding, &conditions).active);
+
+        runtime.dispose();
+    }
+}
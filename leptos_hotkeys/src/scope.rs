@@ -0,0 +1,312 @@
+use leptos::*;
+use std::borrow::{Borrow, Cow};
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+
+/// Name of a scope, like `"editor"` or `"editor.vim.insert"`.
+///
+/// Scope names are case-insensitive: they are lowercased when created, so
+/// `Scope::from("mY_sCoPe") == Scope::from("my_scope")`. Declare the scopes of
+/// an app as constants with [`Scope::from_static`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Scope(Cow<'static, str>);
+
+impl Scope {
+    /// The global `*` scope, active unless an exclusive scope is pushed.
+    pub const GLOBAL: Scope = Scope::from_static("*");
+
+    /// A scope named by a lowercase literal, usable in constants.
+    ///
+    /// # Panics
+    ///
+    /// If `name` has uppercase ASCII letters, at compile time in constants.
+    pub const fn from_static(name: &'static str) -> Self {
+        let bytes = name.as_bytes();
+        let mut i = 0;
+        while i < bytes.len() {
+            assert!(
+                !bytes[i].is_ascii_uppercase(),
+                "scope names must be lowercase"
+            );
+            i += 1;
+        }
+        Scope(Cow::Borrowed(name))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for Scope {
+    fn from(name: &str) -> Self {
+        Scope(Cow::Owned(name.to_lowercase()))
+    }
+}
+
+impl From<String> for Scope {
+    fn from(name: String) -> Self {
+        Scope::from(name.as_str())
+    }
+}
+
+impl From<&String> for Scope {
+    fn from(name: &String) -> Self {
+        Scope::from(name.as_str())
+    }
+}
+
+impl From<&Scope> for Scope {
+    fn from(scope: &Scope) -> Self {
+        scope.clone()
+    }
+}
+
+impl From<Scope> for String {
+    fn from(scope: Scope) -> Self {
+        scope.0.into_owned()
+    }
+}
+
+impl AsRef<str> for Scope {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for Scope {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq<str> for Scope {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for Scope {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl Display for Scope {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Scope {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Scope {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Scope::from)
+    }
+}
+
+/// Types naming scopes, accepted wherever a scope is expected.
+///
+/// It is implemented for strings and [`Scope`], and can be derived for an enum
+/// of unit variants to get typed scopes. Variants are named in snake case
+/// unless renamed with `#[scope = "..."]`:
+///
+/// ```
+/// use leptos_hotkeys::{HotkeyScope, Scope};
+///
+/// #[derive(HotkeyScope)]
+/// enum AppScope {
+///     Editor,
+///     SearchBar,
+///     #[scope = "editor.vim"]
+///     Vim,
+/// }
+///
+/// assert_eq!(AppScope::SearchBar.into_scope(), "search_bar");
+/// assert_eq!(Scope::from(AppScope::Vim), "editor.vim");
+/// ```
+pub trait HotkeyScope {
+    fn into_scope(self) -> Scope;
+}
+
+impl<T: AsRef<str>> HotkeyScope for T {
+    fn into_scope(self) -> Scope {
+        Scope::from(self.as_ref())
+    }
+}
+
+/// Scopes a binding is registered in, either fixed or following a signal so
+/// they can change while the binding is mounted.
+///
+/// Converted from lists of [`HotkeyScope`]s, like `vec!["editor"]` or
+/// `[AppScope::Editor]`, and from signals of them.
+#[derive(Debug, Clone)]
+pub struct Scopes(MaybeSignal<Vec<Scope>>);
+
+impl Scopes {
+    pub(crate) fn get_untracked(&self) -> Vec<Scope> {
+        self.0.get_untracked()
+    }
+
+    #[cfg_attr(feature = "ssr", allow(dead_code))]
+    pub(crate) fn with<R>(&self, f: impl FnOnce(&Vec<Scope>) -> R) -> R {
+        self.0.with(f)
+    }
+
+    /// The signal the scopes follow, or `None` if they are fixed.
+    #[cfg_attr(feature = "ssr", allow(dead_code))]
+    pub(crate) fn signal(&self) -> Option<Signal<Vec<Scope>>> {
+        match self.0 {
+            MaybeSignal::Static(_) => None,
+            MaybeSignal::Dynamic(signal) => Some(signal),
+        }
+    }
+
+    fn follow<T: HotkeyScope + Clone>(signal: impl SignalWith<Value = Vec<T>> + 'static) -> Self {
+        Scopes(MaybeSignal::Dynamic(Signal::derive(move || {
+            signal.with(|scopes| scopes.iter().cloned().map(T::into_scope).collect())
+        })))
+    }
+}
+
+impl<T: HotkeyScope> From<Vec<T>> for Scopes {
+    fn from(scopes: Vec<T>) -> Self {
+        Scopes(MaybeSignal::Static(
+            scopes.into_iter().map(T::into_scope).collect(),
+        ))
+    }
+}
+
+impl<T: HotkeyScope, const N: usize> From<[T; N]> for Scopes {
+    fn from(scopes: [T; N]) -> Self {
+        Scopes::from(Vec::from(scopes))
+    }
+}
+
+impl<T: HotkeyScope + Clone + 'static> From<Signal<Vec<T>>> for Scopes {
+    fn from(scopes: Signal<Vec<T>>) -> Self {
+        Scopes::follow(scopes)
+    }
+}
+
+impl<T: HotkeyScope + Clone + 'static> From<ReadSignal<Vec<T>>> for Scopes {
+    fn from(scopes: ReadSignal<Vec<T>>) -> Self {
+        Scopes::follow(scopes)
+    }
+}
+
+impl<T: HotkeyScope + Clone + 'static> From<RwSignal<Vec<T>>> for Scopes {
+    fn from(scopes: RwSignal<Vec<T>>) -> Self {
+        Scopes::follow(scopes)
+    }
+}
+
+impl<T: HotkeyScope + Clone + PartialEq + 'static> From<Memo<Vec<T>>> for Scopes {
+    fn from(scopes: Memo<Vec<T>>) -> Self {
+        Scopes::follow(scopes)
+    }
+}
+
+/// Whether the scope `pattern` of a binding is active.
+///
+/// Scopes are dotted paths like `editor.vim.insert`, and an active scope
+/// implies its ancestors, `editor.vim` and `editor`. A pattern ending in `.*`
+/// like `editor.*` matches the active descendants of `editor`, but not
+/// `editor` itself.
+pub(crate) fn is_scope_active(pattern: &Scope, active_scopes: &HashSet<Scope>) -> bool {
+    let pattern = pattern.as_str();
+    if let Some(parent) = pattern.strip_suffix(".*") {
+        return active_scopes
+            .iter()
+            .any(|scope| is_descendant(scope, parent));
+    }
+
+    active_scopes.contains(pattern)
+        || active_scopes
+            .iter()
+            .any(|scope| is_descendant(scope, pattern))
+}
+
+/// Whether `scope` is below `ancestor`, like `editor.vim` below `editor`.
+fn is_descendant(scope: &Scope, ancestor: &str) -> bool {
+    scope
+        .as_str()
+        .strip_prefix(ancestor)
+        .is_some_and(|rest| rest.starts_with('.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use leptos_hotkeys_macros::HotkeyScope;
+
+    #[test]
+    fn dotted_scopes() {
+        let active_scopes = HashSet::from([Scope::GLOBAL, Scope::from("Editor.Vim.Insert")]);
+        let is_active = |pattern: &str| is_scope_active(&pattern.into(), &active_scopes);
+
+        assert!(is_active("*"));
+        assert!(is_active("editor.vim.insert"));
+        assert!(is_active("editor.vim"));
+        assert!(is_active("editor"));
+        assert!(is_active("editor.*"));
+        assert!(is_active("editor.vim.*"));
+
+        assert!(!is_active("editor.vim.insert.*"));
+        assert!(!is_active("editor.vim.normal"));
+        assert!(!is_active("edit"));
+        assert!(!is_active("editor.v"));
+        assert!(!is_active("settings.*"));
+    }
+
+    #[test]
+    fn case_insensitive_scopes() {
+        const EDITOR: Scope = Scope::from_static("editor");
+        assert_eq!(Scope::from("mY_sCoPe"), Scope::from("my_scope"));
+        assert_eq!(Scope::from("EDITOR".to_string()), EDITOR);
+        assert_eq!(EDITOR, "editor");
+        assert!(HashSet::from([EDITOR]).contains("editor"));
+    }
+
+    #[test]
+    fn derived_scopes() {
+        #[derive(HotkeyScope)]
+        enum AppScope {
+            Editor,
+            SearchBar,
+            HTTPServer,
+            IOPanel,
+            #[scope = "editor.vim"]
+            Vim,
+        }
+
+        assert_eq!(AppScope::Editor.into_scope(), "editor");
+        assert_eq!(AppScope::SearchBar.into_scope(), "search_bar");
+        assert_eq!(AppScope::HTTPServer.into_scope(), "http_server");
+        assert_eq!(AppScope::IOPanel.into_scope(), "io_panel");
+        assert_eq!(Scope::from(AppScope::Vim), "editor.vim");
+        assert_eq!("Editor".into_scope(), AppScope::Editor.into_scope());
+    }
+
+    #[test]
+    fn derived_scopes_with_crate_path() {
+        mod reexport {
+            pub use crate as hotkeys;
+        }
+
+        #[derive(HotkeyScope)]
+        #[scope(crate = "reexport::hotkeys")]
+        enum AppScope {
+            Sidebar,
+        }
+
+        assert_eq!(Scope::from(AppScope::Sidebar), "sidebar");
+    }
+}
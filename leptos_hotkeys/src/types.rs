@@ -1,30 +1,154 @@
+use crate::{KeyMode, ModifierSide};
 use std::fmt::{Display, Formatter, Result};
 
 pub type Keys = Vec<String>;
 
-#[derive(Debug, PartialEq, Hash, Eq, Default)]
+#[derive(Debug, Clone, PartialEq, Hash, Eq, Default)]
 pub struct KeyboardModifiers {
     pub(crate) alt: bool,
     pub(crate) ctrl: bool,
     pub(crate) meta: bool,
     pub(crate) shift: bool,
+    /// The `mod` modifier, resolved at runtime with [`Platform`].
+    pub(crate) primary: bool,
+
+    pub(crate) alt_side: ModifierSide,
+    pub(crate) ctrl_side: ModifierSide,
+    pub(crate) meta_side: ModifierSide,
+    pub(crate) shift_side: ModifierSide,
+
+    /// Modifier states read with `KeyboardEvent.getModifierState`: `Some(true)`
+    /// when they must be active, `Some(false)` when they must not, and `None`
+    /// when they don't matter.
+    pub(crate) alt_graph: Option<bool>,
+    pub(crate) caps_lock: Option<bool>,
+    pub(crate) fn_key: Option<bool>,
+    pub(crate) num_lock: Option<bool>,
+}
+
+impl KeyboardModifiers {
+    /// Replaces `primary` with the modifier it stands for on `platform`.
+    pub(crate) fn resolve(&self, platform: Platform) -> KeyboardModifiers {
+        let mut modifiers = self.clone();
+        if modifiers.primary {
+            modifiers.primary = false;
+            match platform {
+                Platform::Apple => modifiers.meta = true,
+                Platform::Other => modifiers.ctrl = true,
+            }
+        }
+        modifiers
+    }
+
+    /// Whether the modifiers of two chords, resolved for a platform, can be
+    /// held at the same time: the same modifiers, on compatible sides, and
+    /// without contradicting modifier states.
+    pub(crate) fn overlaps(&self, other: &KeyboardModifiers) -> bool {
+        let held = [
+            (self.alt, self.alt_side, other.alt, other.alt_side),
+            (self.ctrl, self.ctrl_side, other.ctrl, other.ctrl_side),
+            (self.meta, self.meta_side, other.meta, other.meta_side),
+            (self.shift, self.shift_side, other.shift, other.shift_side),
+        ];
+        let states = [
+            (self.alt_graph, other.alt_graph),
+            (self.caps_lock, other.caps_lock),
+            (self.fn_key, other.fn_key),
+            (self.num_lock, other.num_lock),
+        ];
+
+        self.primary == other.primary
+            && held
+                .into_iter()
+                .all(|(held, side, other_held, other_side)| {
+                    held == other_held
+                        && (side == ModifierSide::Any
+                            || other_side == ModifierSide::Any
+                            || side == other_side)
+                })
+            && states
+                .into_iter()
+                .all(|(state, other_state)| state.zip(other_state).is_none_or(|(a, b)| a == b))
+    }
+}
+
+impl KeyboardModifiers {
+    /// Labels of the held modifiers and active modifier states, resolved for
+    /// `platform`, in the order of the platform: `⌃⌥⇧⌘` on Apple platforms and
+    /// `Ctrl`, `Alt`, `Shift`, `Meta` elsewhere.
+    pub(crate) fn labels(&self, platform: Platform) -> Vec<String> {
+        let modifiers = self.resolve(platform);
+        let (ctrl, alt, shift, meta, caps_lock) = match platform {
+            Platform::Apple => ("⌃", "⌥", "⇧", "⌘", "⇪"),
+            Platform::Other => ("Ctrl", "Alt", "Shift", "Meta", "Caps Lock"),
+        };
+        let held = [
+            (modifiers.ctrl, modifiers.ctrl_side, ctrl),
+            (modifiers.alt, modifiers.alt_side, alt),
+            (modifiers.shift, modifiers.shift_side, shift),
+            (modifiers.meta, modifiers.meta_side, meta),
+        ];
+        let states = [
+            (modifiers.alt_graph, "AltGr"),
+            (modifiers.caps_lock, caps_lock),
+            (modifiers.fn_key, "Fn"),
+            (modifiers.num_lock, "Num Lock"),
+        ];
+
+        let held =
+            held.into_iter()
+                .filter(|(held, _, _)| *held)
+                .map(|(_, side, label)| match side {
+                    ModifierSide::Any => label.to_string(),
+                    ModifierSide::Left => format!("Left {label}"),
+                    ModifierSide::Right => format!("Right {label}"),
+                });
+        let states = states
+            .into_iter()
+            .filter(|(state, _)| *state == Some(true))
+            .map(|(_, label)| label.to_string());
+        held.chain(states).collect()
+    }
 }
 
 impl Display for KeyboardModifiers {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         let mut modifiers = Vec::new();
 
+        let sided = |side, any, left, right| match side {
+            ModifierSide::Any => any,
+            ModifierSide::Left => left,
+            ModifierSide::Right => right,
+        };
+
         if self.alt {
-            modifiers.push("Alt");
+            modifiers.push(sided(self.alt_side, "Alt", "AltLeft", "AltRight"));
         }
         if self.ctrl {
-            modifiers.push("Ctrl");
+            modifiers.push(sided(self.ctrl_side, "Ctrl", "ControlLeft", "ControlRight"));
         }
         if self.meta {
-            modifiers.push("Meta");
+            modifiers.push(sided(self.meta_side, "Meta", "MetaLeft", "MetaRight"));
+        }
+        if self.primary {
+            modifiers.push("Mod");
         }
         if self.shift {
-            modifiers.push("Shift");
+            modifiers.push(sided(self.shift_side, "Shift", "ShiftLeft", "ShiftRight"));
+        }
+
+        let states = [
+            (self.alt_graph, "AltGraph", "!AltGraph"),
+            (self.caps_lock, "CapsLock", "!CapsLock"),
+            (self.fn_key, "Fn", "!Fn"),
+            (self.num_lock, "NumLock", "!NumLock"),
+        ];
+        for (state, active, inactive) in states {
+            match state {
+                Some(true) => modifiers.push(active),
+                Some(false) => modifiers.push(inactive),
+                None => {}
+            }
         }
 
         match modifiers.is_empty() {
@@ -33,3 +157,75 @@ impl Display for KeyboardModifiers {
         }
     }
 }
+
+/// Lowercased value of `event` in `key_mode`, as written in key names.
+#[cfg_attr(feature = "ssr", allow(dead_code))]
+pub(crate) fn event_value(event: &web_sys::KeyboardEvent, key_mode: KeyMode) -> String {
+    let value = match key_mode {
+        KeyMode::Key => event.key(),
+        KeyMode::Code => event.code(),
+    };
+    match value.as_str() {
+        " " | "Space" => "spacebar".to_string(),
+        value => value.to_lowercase(),
+    }
+}
+
+/// Platform the `mod` modifier is resolved for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Platform {
+    /// macOS and iOS, where `mod` is the Command (Meta) key.
+    Apple,
+    /// Any other platform, where `mod` is the Control key.
+    Other,
+}
+
+impl Platform {
+    /// Detects the platform from `navigator.userAgentData`, falling back to
+    /// `navigator.platform`. Always [`Platform::Other`] when rendering on the
+    /// server or outside of a browser.
+    pub fn detect() -> Self {
+        #[cfg(not(feature = "ssr"))]
+        if cfg!(target_arch = "wasm32") {
+            let navigator = leptos::window().navigator();
+            let name = js_sys::Reflect::get(&navigator, &"userAgentData".into())
+                .ok()
+                .filter(|data| data.is_object())
+                .and_then(|data| js_sys::Reflect::get(&data, &"platform".into()).ok())
+                .and_then(|platform| platform.as_string())
+                .or_else(|| navigator.platform().ok())
+                .unwrap_or_default();
+            return Self::from_name(&name);
+        }
+
+        Self::Other
+    }
+
+    #[cfg_attr(feature = "ssr", allow(dead_code))]
+    fn from_name(name: &str) -> Self {
+        let name = name.to_lowercase();
+        if ["mac", "iphone", "ipad", "ipod", "ios"]
+            .iter()
+            .any(|apple| name.contains(apple))
+        {
+            Self::Apple
+        } else {
+            Self::Other
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn platform_from_name() {
+        assert_eq!(Platform::from_name("macOS"), Platform::Apple);
+        assert_eq!(Platform::from_name("MacIntel"), Platform::Apple);
+        assert_eq!(Platform::from_name("iPhone"), Platform::Apple);
+        assert_eq!(Platform::from_name("Win32"), Platform::Other);
+        assert_eq!(Platform::from_name("Linux x86_64"), Platform::Other);
+        assert_eq!(Platform::from_name(""), Platform::Other);
+    }
+}
@@ -1,99 +1,579 @@
+use crate::hotkey::{parse_chord, parse_key_combination};
+use crate::registry::Binding;
+use crate::{
+    use_hotkeys_context, Hotkey, HotkeyEvent, HotkeyHandle, HotkeyOptions, HotkeyParseError,
+    HotkeyRegistrationError, Scope, Scopes, WhenClause,
+};
 use leptos::{html::ElementDescriptor, *};
+use std::collections::HashSet;
 
 pub fn use_hotkeys_scoped(
-    #[cfg_attr(feature = "ssr", allow(unused_variables))] key_combination: String,
-    #[cfg_attr(feature = "ssr", allow(unused_variables))] on_triggered: Callback<()>,
-    #[cfg_attr(feature = "ssr", allow(unused_variables))] scopes: Vec<String>,
-) {
+    key_combination: impl Into<MaybeSignal<String>>,
+    on_triggered: Callback<HotkeyEvent>,
+    scopes: impl Into<Scopes>,
+) -> HotkeyHandle {
+    use_hotkeys_scoped_with_options(
+        key_combination,
+        on_triggered,
+        scopes,
+        HotkeyOptions::default(),
+    )
+}
+
+/// Like [`use_hotkeys_scoped`], but returns an error if `key_combination`
+/// can't be parsed instead of logging it.
+pub fn try_use_hotkeys_scoped(
+    key_combination: impl Into<MaybeSignal<String>>,
+    on_triggered: Callback<HotkeyEvent>,
+    scopes: impl Into<Scopes>,
+) -> Result<HotkeyHandle, HotkeyParseError> {
+    try_use_hotkeys_scoped_with_options(
+        key_combination,
+        on_triggered,
+        scopes,
+        HotkeyOptions::default(),
+    )
+}
+
+/// Like [`use_hotkeys_scoped`], with per-binding [`HotkeyOptions`].
+pub fn use_hotkeys_scoped_with_options(
+    key_combination: impl Into<MaybeSignal<String>>,
+    on_triggered: Callback<HotkeyEvent>,
+    scopes: impl Into<Scopes>,
+    options: HotkeyOptions,
+) -> HotkeyHandle {
+    let key_combination = key_combination.into();
+    try_use_hotkeys_scoped_with_options(key_combination.clone(), on_triggered, scopes, options)
+        .unwrap_or_else(|err| {
+            let key_combination = key_combination.get_untracked();
+            logging::error!("invalid hotkey {:?}: {}", key_combination, err);
+            HotkeyHandle::unregistered(use_hotkeys_context())
+        })
+}
+
+/// Like [`try_use_hotkeys_scoped`], with per-binding [`HotkeyOptions`].
+pub fn try_use_hotkeys_scoped_with_options(
+    key_combination: impl Into<MaybeSignal<String>>,
+    on_triggered: Callback<HotkeyEvent>,
+    scopes: impl Into<Scopes>,
+    options: HotkeyOptions,
+) -> Result<HotkeyHandle, HotkeyParseError> {
+    let parsed_keys = parse_reactive(key_combination.into(), parse_key_combination)?;
+    Ok(use_parsed_hotkeys_scoped(
+        parsed_keys,
+        on_triggered,
+        scopes,
+        options,
+    ))
+}
+
+/// Registers hotkeys that fire while the when clause `when` holds, like
+/// `"editor && !modalOpen || focusInSearch"`, instead of in a list of scopes.
+pub fn use_hotkeys_when(
+    key_combination: impl Into<MaybeSignal<String>>,
+    on_triggered: Callback<HotkeyEvent>,
+    when: &str,
+) -> HotkeyHandle {
+    let key_combination = key_combination.into();
+    try_use_hotkeys_when(key_combination.clone(), on_triggered, when).unwrap_or_else(|err| {
+        let key_combination = key_combination.get_untracked();
+        logging::error!("can't register hotkey {:?}: {}", key_combination, err);
+        HotkeyHandle::unregistered(use_hotkeys_context())
+    })
+}
+
+/// Like [`use_hotkeys_when`], but returns an error if `key_combination` or
+/// `when` can't be parsed instead of logging it.
+pub fn try_use_hotkeys_when(
+    key_combination: impl Into<MaybeSignal<String>>,
+    on_triggered: Callback<HotkeyEvent>,
+    when: &str,
+) -> Result<HotkeyHandle, HotkeyRegistrationError> {
+    let parsed_keys = parse_reactive(key_combination.into(), parse_key_combination)?;
+    let when = when.parse::<WhenClause>()?;
+    Ok(use_parsed_hotkeys_scoped(
+        parsed_keys,
+        on_triggered,
+        Vec::<Scope>::new(),
+        HotkeyOptions::new().when(when),
+    ))
+}
+
+/// Registers a single chord like `"space"` with a callback when it is pressed
+/// and another when it is released, for push-to-talk for example.
+///
+/// Repeated key presses while the chord is held down are ignored.
+pub fn use_hotkeys_hold(
+    key_combination: impl Into<MaybeSignal<String>>,
+    on_press: Callback<HotkeyEvent>,
+    on_release: Callback<HotkeyEvent>,
+    scopes: impl Into<Scopes>,
+) -> HotkeyHandle {
+    let key_combination = key_combination.into();
+    try_use_hotkeys_hold(key_combination.clone(), on_press, on_release, scopes).unwrap_or_else(
+        |err| {
+            let key_combination = key_combination.get_untracked();
+            logging::error!("invalid hotkey {:?}: {}", key_combination, err);
+            HotkeyHandle::unregistered(use_hotkeys_context())
+        },
+    )
+}
+
+/// Like [`use_hotkeys_hold`], but returns an error if `key_combination` isn't
+/// a single chord instead of logging it.
+pub fn try_use_hotkeys_hold(
+    key_combination: impl Into<MaybeSignal<String>>,
+    on_press: Callback<HotkeyEvent>,
+    on_release: Callback<HotkeyEvent>,
+    scopes: impl Into<Scopes>,
+) -> Result<HotkeyHandle, HotkeyParseError> {
+    let parsed_keys = parse_reactive(key_combination.into(), |key_combination| {
+        parse_chord(key_combination).map(|hotkey| HashSet::from([hotkey]))
+    })?;
+    let on_triggered = Callback::new(move |event: HotkeyEvent| {
+        if event.released {
+            Callable::call(&on_release, event);
+        } else {
+            Callable::call(&on_press, event);
+        }
+    });
+    Ok(use_parsed_hotkeys_scoped(
+        parsed_keys,
+        on_triggered,
+        scopes,
+        HotkeyOptions::new().on_keyup(true).allow_repeat(false),
+    ))
+}
+
+/// Registers hotkeys already parsed, by the `use_hotkeys!` macro for example.
+#[doc(hidden)]
+pub fn use_parsed_hotkeys_scoped(
+    parsed_keys: impl Into<MaybeSignal<HashSet<Hotkey>>>,
+    on_triggered: Callback<HotkeyEvent>,
+    scopes: impl Into<Scopes>,
+    options: HotkeyOptions,
+) -> HotkeyHandle {
+    let hotkeys_context = use_hotkeys_context();
+    let parsed_keys = parsed_keys.into();
+    let scopes = scopes.into();
+    let id = hotkeys_context.register(Binding::new(
+        &parsed_keys.get_untracked(),
+        &scopes.get_untracked(),
+        on_triggered,
+        &options,
+        false,
+    ));
+
     #[cfg(not(feature = "ssr"))]
     {
-        use crate::hotkey::{is_hotkey_match, is_last_key_match};
-        use crate::{use_hotkeys_context, Hotkey};
-        use std::collections::HashSet;
-
-        let parsed_keys: HashSet<Hotkey> = key_combination.split(',').map(Hotkey::new).collect();
+        use crate::matcher::Matcher;
 
-        let hotkeys_context = use_hotkeys_context();
+        let matcher = Matcher::new(&hotkeys_context, id, parsed_keys.get_untracked(), &options);
+        let when = options.when;
+        follow_hotkeys(hotkeys_context, id, options.action, parsed_keys, matcher);
+        follow_scopes(hotkeys_context, id, &scopes);
+        if options.keyup {
+            release_on_stop(hotkeys_context, id, &scopes, &when, matcher, on_triggered);
+        }
 
         create_effect(move |_| {
-            let active_scopes = hotkeys_context.active_scopes.get();
-            let within_scope = scopes.iter().any(|scope| active_scopes.contains(scope));
-
-            if !within_scope {
+            if !scopes.with(|scopes| hotkeys_context.is_active(scopes, when.as_ref())) {
                 return;
             }
 
-            let mut keys_pressed = hotkeys_context.keys_pressed.get();
-            if !is_last_key_match(&parsed_keys, &keys_pressed) {
+            let keys_pressed = hotkeys_context.keys_pressed.get();
+            if !hotkeys_context.is_running(id) {
                 return;
             }
-
-            if let Some(matching_hotkey) = parsed_keys
-                .iter()
-                .find(|hotkey| is_hotkey_match(hotkey, &mut keys_pressed.key_map))
-            {
-                if cfg!(feature = "debug") {
-                    let message = format!("%cfiring hotkey: {}", &matching_hotkey);
-                    web_sys::console::log_2(
-                        &wasm_bindgen::JsValue::from_str(&message),
-                        &wasm_bindgen::JsValue::from_str("color: #39FF14;"),
-                    );
-                }
-                Callable::call(&on_triggered, ());
+            let event = match &keys_pressed.released {
+                Some(released) => matcher.find_release(&hotkeys_context, released),
+                None => matcher.find_match(&hotkeys_context, &keys_pressed),
+            };
+            if let Some(event) = event {
+                fire(on_triggered, event);
             }
         });
     }
+
+    HotkeyHandle::new(hotkeys_context, id)
 }
 
 pub fn use_hotkeys_ref<T>(
+    node_ref: NodeRef<T>,
+    key_combination: impl Into<MaybeSignal<String>>,
+    on_triggered: Callback<HotkeyEvent>,
+    scopes: impl Into<Scopes>,
+) -> HotkeyHandle
+where
+    T: ElementDescriptor + 'static + Clone,
+{
+    use_hotkeys_ref_with_options(
+        node_ref,
+        key_combination,
+        on_triggered,
+        scopes,
+        HotkeyOptions::default(),
+    )
+}
+
+/// Like [`use_hotkeys_ref`], but returns an error if `key_combination` can't
+/// be parsed instead of logging it.
+pub fn try_use_hotkeys_ref<T>(
+    node_ref: NodeRef<T>,
+    key_combination: impl Into<MaybeSignal<String>>,
+    on_triggered: Callback<HotkeyEvent>,
+    scopes: impl Into<Scopes>,
+) -> Result<HotkeyHandle, HotkeyParseError>
+where
+    T: ElementDescriptor + 'static + Clone,
+{
+    try_use_hotkeys_ref_with_options(
+        node_ref,
+        key_combination,
+        on_triggered,
+        scopes,
+        HotkeyOptions::default(),
+    )
+}
+
+/// Like [`use_hotkeys_ref`], with per-binding [`HotkeyOptions`].
+pub fn use_hotkeys_ref_with_options<T>(
+    node_ref: NodeRef<T>,
+    key_combination: impl Into<MaybeSignal<String>>,
+    on_triggered: Callback<HotkeyEvent>,
+    scopes: impl Into<Scopes>,
+    options: HotkeyOptions,
+) -> HotkeyHandle
+where
+    T: ElementDescriptor + 'static + Clone,
+{
+    let key_combination = key_combination.into();
+    try_use_hotkeys_ref_with_options(
+        node_ref,
+        key_combination.clone(),
+        on_triggered,
+        scopes,
+        options,
+    )
+    .unwrap_or_else(|err| {
+        let key_combination = key_combination.get_untracked();
+        logging::error!("invalid hotkey {:?}: {}", key_combination, err);
+        HotkeyHandle::unregistered(use_hotkeys_context())
+    })
+}
+
+/// Like [`try_use_hotkeys_ref`], with per-binding [`HotkeyOptions`].
+pub fn try_use_hotkeys_ref_with_options<T>(
+    node_ref: NodeRef<T>,
+    key_combination: impl Into<MaybeSignal<String>>,
+    on_triggered: Callback<HotkeyEvent>,
+    scopes: impl Into<Scopes>,
+    options: HotkeyOptions,
+) -> Result<HotkeyHandle, HotkeyParseError>
+where
+    T: ElementDescriptor + 'static + Clone,
+{
+    let parsed_keys = parse_reactive(key_combination.into(), parse_key_combination)?;
+    Ok(use_parsed_hotkeys_ref(
+        node_ref,
+        parsed_keys,
+        on_triggered,
+        scopes,
+        options,
+    ))
+}
+
+/// Registers hotkeys already parsed, by the `use_hotkeys_ref!` macro for example.
+#[doc(hidden)]
+pub fn use_parsed_hotkeys_ref<T>(
     #[cfg_attr(feature = "ssr", allow(unused_variables))] node_ref: NodeRef<T>,
-    #[cfg_attr(feature = "ssr", allow(unused_variables))] key_combination: String,
-    #[cfg_attr(feature = "ssr", allow(unused_variables))] on_triggered: Callback<()>,
-    #[cfg_attr(feature = "ssr", allow(unused_variables))] scopes: Vec<String>,
-) where
+    parsed_keys: impl Into<MaybeSignal<HashSet<Hotkey>>>,
+    on_triggered: Callback<HotkeyEvent>,
+    scopes: impl Into<Scopes>,
+    options: HotkeyOptions,
+) -> HotkeyHandle
+where
     T: ElementDescriptor + 'static + Clone,
 {
+    let hotkeys_context = use_hotkeys_context();
+    let parsed_keys = parsed_keys.into();
+    let scopes = scopes.into();
+    let id = hotkeys_context.register(Binding::new(
+        &parsed_keys.get_untracked(),
+        &scopes.get_untracked(),
+        on_triggered,
+        &options,
+        true,
+    ));
+
     #[cfg(not(feature = "ssr"))]
-    create_effect(move |_| {
-        use crate::hotkey::{is_hotkey_match, is_last_key_match};
-        use crate::{use_hotkeys_context, Hotkey};
+    {
+        use crate::matcher::Matcher;
         use leptos::ev::DOMEventResponder;
-        use std::collections::HashSet;
-
-        let parsed_keys: HashSet<Hotkey> = key_combination.split(',').map(Hotkey::new).collect();
-        let scopes = scopes.clone();
-        if let Some(element) = node_ref.get() {
-            let keydown_closure = move |_event: web_sys::KeyboardEvent| {
-                let hotkeys_context = use_hotkeys_context();
-                let active_scopes = hotkeys_context.active_scopes.get();
-                let mut pressed_keys = hotkeys_context.keys_pressed.get();
-                let within_scope = scopes.iter().any(|scope| active_scopes.contains(scope));
-
-                if !within_scope {
-                    return;
-                }
 
-                if !is_last_key_match(&parsed_keys, &pressed_keys) {
-                    return;
-                }
+        let matcher = Matcher::new(&hotkeys_context, id, parsed_keys.get_untracked(), &options);
+        let when = options.when;
+        follow_hotkeys(hotkeys_context, id, options.action, parsed_keys, matcher);
+        follow_scopes(hotkeys_context, id, &scopes);
+        if options.keyup {
+            release_on_stop(hotkeys_context, id, &scopes, &when, matcher, on_triggered);
+        }
 
-                if let Some(matching_hotkey) = parsed_keys
-                    .iter()
-                    .find(|hotkey| is_hotkey_match(hotkey, &mut pressed_keys.key_map))
-                {
-                    if cfg!(feature = "debug") {
-                        let message = format!("%cfiring hotkey: {}", &matching_hotkey);
-                        web_sys::console::log_2(
-                            &wasm_bindgen::JsValue::from_str(&message),
-                            &wasm_bindgen::JsValue::from_str("color: #39FF14;"),
-                        );
+        create_effect(move |_| {
+            let scopes = scopes.clone();
+            let when = when.clone();
+            if let Some(element) = node_ref.get() {
+                let is_running = move || {
+                    hotkeys_context.is_running(id)
+                        && scopes.with(|scopes| hotkeys_context.is_active(scopes, when.as_ref()))
+                };
+                let keydown_closure = {
+                    let is_running = is_running.clone();
+                    move |_event: web_sys::KeyboardEvent| {
+                        let pressed_keys = hotkeys_context.keys_pressed.get();
+                        if !is_running() {
+                            return;
+                        }
+                        if let Some(event) = matcher.find_match(&hotkeys_context, &pressed_keys) {
+                            fire(on_triggered, event);
+                        }
                     }
-                    Callable::call(&on_triggered, ());
+                };
+                let keyup_closure = move |event: web_sys::KeyboardEvent| {
+                    if !is_running() {
+                        return;
+                    }
+                    if let Some(event) = matcher.find_release(&hotkeys_context, &event) {
+                        fire(on_triggered, event);
+                    }
+                };
+
+                // needs `leptos::ev::DOMEventResponder`
+                let _ = element
+                    .add(ev::keydown, keydown_closure)
+                    .add(ev::keyup, keyup_closure);
+            }
+        });
+    }
+
+    HotkeyHandle::new(hotkeys_context, id)
+}
+
+/// Runs the callback of a binding for `event`.
+#[cfg(not(feature = "ssr"))]
+fn fire(on_triggered: Callback<HotkeyEvent>, event: HotkeyEvent) {
+    if cfg!(feature = "debug") {
+        logging::log!("firing hotkey: {}", event.hotkey);
+    }
+    Callable::call(&on_triggered, event);
+}
+
+/// Fires the release of the hotkey held down for a binding firing on keyup
+/// when the binding stops running or the window loses focus, as its key
+/// release would be missed.
+#[cfg(not(feature = "ssr"))]
+fn release_on_stop(
+    hotkeys_context: crate::HotkeysContext,
+    id: usize,
+    scopes: &Scopes,
+    when: &Option<WhenClause>,
+    matcher: crate::matcher::Matcher,
+    on_triggered: Callback<HotkeyEvent>,
+) {
+    let scopes = scopes.clone();
+    let when = when.clone();
+    create_effect(move |_| {
+        let running = hotkeys_context
+            .bindings
+            .with(|bindings| bindings.get(&id).is_some_and(Binding::is_enabled))
+            && scopes.with(|scopes| hotkeys_context.is_active(scopes, when.as_ref()));
+        // a blur clears the keys without a release
+        let blurred = hotkeys_context
+            .keys_pressed
+            .with(|keys| keys.key_map.is_empty() && keys.released.is_none());
+        if running && !blurred {
+            return;
+        }
+        if let Some(event) = matcher.release_held() {
+            fire(on_triggered, event);
+        }
+    });
+}
+
+/// Parses `key_combination`, and parses it again when it follows a signal
+/// and changes. Invalid changes are logged and keep the previous hotkeys.
+fn parse_reactive(
+    key_combination: MaybeSignal<String>,
+    parse: fn(&str) -> Result<HashSet<Hotkey>, HotkeyParseError>,
+) -> Result<MaybeSignal<HashSet<Hotkey>>, HotkeyParseError> {
+    let initial = parse(&key_combination.get_untracked())?;
+    let MaybeSignal::Dynamic(key_combination) = key_combination else {
+        return Ok(MaybeSignal::Static(initial));
+    };
+
+    let hotkeys = create_memo(move |previous: Option<&HashSet<Hotkey>>| {
+        key_combination.with(|key_combination| {
+            parse(key_combination).unwrap_or_else(|err| {
+                logging::error!("invalid hotkey {:?}: {}", key_combination, err);
+                previous.unwrap_or(&initial).clone()
+            })
+        })
+    });
+    Ok(hotkeys.into())
+}
+
+/// Keeps the hotkeys of a binding in sync with `hotkeys` when they follow a
+/// signal, and with the keymap of the context when it is named `action`.
+#[cfg(not(feature = "ssr"))]
+fn follow_hotkeys(
+    hotkeys_context: crate::HotkeysContext,
+    id: usize,
+    action: Option<String>,
+    default_hotkeys: MaybeSignal<HashSet<Hotkey>>,
+    matcher: crate::matcher::Matcher,
+) {
+    if action.is_none() && matches!(default_hotkeys, MaybeSignal::Static(_)) {
+        return;
+    }
+
+    create_effect(move |previous: Option<HashSet<Hotkey>>| {
+        let hotkeys = default_hotkeys.with(|default_hotkeys| match &action {
+            Some(action) => hotkeys_context.action_hotkeys(action, default_hotkeys),
+            None => default_hotkeys.clone(),
+        });
+        if previous.as_ref() != Some(&hotkeys) {
+            matcher.set_hotkeys(&hotkeys_context, hotkeys.clone());
+            hotkeys_context.bindings.update(|bindings| {
+                if let Some(binding) = bindings.get_mut(&id) {
+                    binding.hotkeys = hotkeys.clone();
                 }
-            };
+            });
+        }
+        hotkeys
+    });
+}
+
+/// Keeps the scopes of a binding in sync with `scopes` when they follow a
+/// signal.
+#[cfg(not(feature = "ssr"))]
+fn follow_scopes(hotkeys_context: crate::HotkeysContext, id: usize, scopes: &Scopes) {
+    let Some(scopes) = scopes.signal() else {
+        return;
+    };
 
-            // needs `leptos::ev::DOMEventResponder`
-            let _ = element.add(ev::keydown, keydown_closure);
+    create_effect(move |previous: Option<Vec<Scope>>| {
+        let scopes = scopes.get();
+        if previous.is_some_and(|previous| previous != scopes) {
+            hotkeys_context.bindings.update(|bindings| {
+                if let Some(binding) = bindings.get_mut(&id) {
+                    binding.scopes = scopes.clone();
+                }
+            });
         }
+        scopes
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reparse_key_combination() {
+        let runtime = create_runtime();
+        let key_combination = create_rw_signal("ctrl+k".to_string());
+        let hotkeys = parse_reactive(key_combination.into(), parse_key_combination).unwrap();
+        assert_eq!(hotkeys.get(), HashSet::from([Hotkey::new("ctrl+k")]));
+
+        key_combination.set("ctrl+shift+k".to_string());
+        assert_eq!(hotkeys.get(), HashSet::from([Hotkey::new("ctrl+shift+k")]));
+        key_combination.set("ctrl+".to_string());
+        assert_eq!(hotkeys.get(), HashSet::from([Hotkey::new("ctrl+shift+k")]));
+
+        let invalid = MaybeSignal::from("ctlr+k".to_string());
+        assert!(parse_reactive(invalid, parse_key_combination).is_err());
+
+        runtime.dispose();
+    }
+
+    #[test]
+    #[cfg(not(feature = "ssr"))]
+    fn follow_signals() {
+        use crate::{provide_hotkeys_context, scopes};
+
+        let runtime = create_runtime();
+        let hotkeys_context =
+            provide_hotkeys_context(NodeRef::<html::Div>::new(), false, scopes!("editor"));
+        let key_combination = create_rw_signal("ctrl+k".to_string());
+        let scopes = create_rw_signal(vec!["editor"]);
+        use_hotkeys_scoped(key_combination, Callback::new(|_| ()), scopes);
+
+        let registered = move || hotkeys_context.registry().get_untracked()[0].clone();
+        assert_eq!(registered().hotkeys, [Hotkey::new("ctrl+k")]);
+        assert!(registered().active);
+
+        key_combination.set("ctrl+p".to_string());
+        scopes.set(vec!["modal"]);
+        assert_eq!(registered().hotkeys, [Hotkey::new("ctrl+p")]);
+        assert_eq!(registered().scopes, [Scope::from("modal")]);
+        assert!(!registered().active);
+
+        runtime.dispose();
+    }
+
+    #[test]
+    #[cfg(not(feature = "ssr"))]
+    fn release_held_hotkey() {
+        use crate::context::KeyPresses;
+        use crate::matcher::Matcher;
+        use crate::provide_hotkeys_context;
+
+        let runtime = create_runtime();
+        let hotkeys_context = provide_hotkeys_context(NodeRef::<html::Div>::new(), false, ["*"]);
+        let events = store_value(Vec::<HotkeyEvent>::new());
+        let on_triggered =
+            Callback::new(move |event| events.update_value(|events| events.push(event)));
+        let handle = use_hotkeys_scoped("space", on_triggered, ["*"]);
+        let id = handle.id().unwrap();
+        let space = Hotkey::new("space");
+        let options = HotkeyOptions::new().on_keyup(true);
+        let matcher = Matcher::new(
+            &hotkeys_context,
+            id,
+            HashSet::from([space.clone()]),
+            &options,
+        );
+        release_on_stop(
+            hotkeys_context,
+            id,
+            &["*"].into(),
+            &None,
+            matcher,
+            on_triggered,
+        );
+        let released = move || {
+            events.with_value(|events| {
+                events
+                    .iter()
+                    .filter(|event| event.released && event.event.is_none())
+                    .count()
+            })
+        };
+
+        matcher.hold(space.clone());
+        handle.pause();
+        assert_eq!(released(), 1);
+        handle.resume();
+
+        matcher.hold(space.clone());
+        hotkeys_context.keys_pressed.set(KeyPresses::default());
+        assert_eq!(released(), 2);
+
+        matcher.hold(space.clone());
+        Callable::call(&hotkeys_context.disable_scope, Scope::GLOBAL);
+        assert_eq!(released(), 3);
+        assert!(matcher.release_held().is_none());
+
+        runtime.dispose();
+    }
+}
@@ -0,0 +1,82 @@
+use crate::{use_hotkeys_context, HotkeyScope};
+use leptos::html::ElementDescriptor;
+use leptos::*;
+use std::cell::Cell;
+use std::rc::Rc;
+
+/// Activates `scope` until the current component is cleaned up.
+///
+/// Several mounted components can use the same scope: it stays active until
+/// the last one is cleaned up.
+pub fn use_scope(scope: impl HotkeyScope) {
+    let hotkeys_context = use_hotkeys_context();
+    let scope = scope.into_scope();
+    hotkeys_context.retain_scope(scope.clone());
+    on_cleanup(move || hotkeys_context.release_scope(scope));
+}
+
+/// Activates `scope` while keyboard focus is within the element of `node_ref`,
+/// so the bindings of a panel only apply while it's focused.
+///
+/// Like [`use_scope`], the scope stays active while any of its users keeps it.
+pub fn use_focus_scope<T>(
+    #[cfg_attr(feature = "ssr", allow(unused_variables))] node_ref: NodeRef<T>,
+    scope: impl HotkeyScope,
+) where
+    T: ElementDescriptor + 'static + Clone,
+{
+    let hotkeys_context = use_hotkeys_context();
+    let scope = scope.into_scope();
+    let focused = Rc::new(Cell::new(false));
+
+    let set_focused = move |is_focused: bool| {
+        if focused.replace(is_focused) != is_focused {
+            match is_focused {
+                true => hotkeys_context.retain_scope(scope.clone()),
+                false => hotkeys_context.release_scope(scope.clone()),
+            }
+        }
+    };
+
+    #[cfg(not(feature = "ssr"))]
+    {
+        use wasm_bindgen::JsCast;
+
+        let set_focused = set_focused.clone();
+        node_ref.on_load(move |element| {
+            let on_focusin = set_focused.clone();
+            let on_focusout = set_focused;
+            let _ =
+                element
+                    .on(ev::focusin, move |_| on_focusin(true))
+                    .on(ev::focusout, move |event| {
+                        // focus moving to a descendant doesn't leave the element
+                        let current = event
+                            .current_target()
+                            .and_then(|target| target.dyn_into::<web_sys::Node>().ok());
+                        let related = event
+                            .related_target()
+                            .and_then(|target| target.dyn_into::<web_sys::Node>().ok());
+                        let within =
+                            current.is_some_and(|current| current.contains(related.as_ref()));
+                        if !within {
+                            on_focusout(false);
+                        }
+                    });
+        });
+    }
+
+    on_cleanup(move || set_focused(false));
+}
+
+/// Activates `scope` while its children are mounted, with [`use_scope`].
+#[component]
+pub fn ActiveScope(
+    /// The scope, like `"sidebar"`.
+    #[prop(into)]
+    scope: crate::Scope,
+    children: Children,
+) -> impl IntoView {
+    use_scope(scope);
+    children()
+}
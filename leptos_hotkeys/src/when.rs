@@ -0,0 +1,275 @@
+use crate::scope::is_scope_active;
+use crate::{HotkeyParseError, Scope};
+use core::str::FromStr;
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+
+/// A condition gating a binding, like `editor && !modalOpen || focusInSearch`.
+///
+/// Names are true when they are an active scope or a context key set to `true`
+/// with [`HotkeysContext::set_context_key`](crate::HotkeysContext::set_context_key).
+/// `!` binds tighter than `&&`, which binds tighter than `||`, and parentheses
+/// group sub-expressions.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WhenClause(Expr);
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Expr {
+    Name(String),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+/// Error returned when a when clause can't be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WhenParseError {
+    message: String,
+    span: Range<usize>,
+}
+
+impl WhenParseError {
+    /// Byte range of the input that caused the error.
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+}
+
+impl Display for WhenParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} at {}..{}",
+            self.message, self.span.start, self.span.end
+        )
+    }
+}
+
+impl std::error::Error for WhenParseError {}
+
+/// Error returned when a binding can't be registered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HotkeyRegistrationError {
+    Hotkey(HotkeyParseError),
+    When(WhenParseError),
+}
+
+impl Display for HotkeyRegistrationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Hotkey(err) => write!(f, "invalid hotkey: {}", err),
+            Self::When(err) => write!(f, "invalid when clause: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for HotkeyRegistrationError {}
+
+impl From<HotkeyParseError> for HotkeyRegistrationError {
+    fn from(err: HotkeyParseError) -> Self {
+        Self::Hotkey(err)
+    }
+}
+
+impl From<WhenParseError> for HotkeyRegistrationError {
+    fn from(err: WhenParseError) -> Self {
+        Self::When(err)
+    }
+}
+
+impl WhenClause {
+    /// Evaluates the clause, names being true when they are active scopes,
+    /// matched like the scopes of a binding, or in `context_keys`, the context
+    /// keys set to `true`.
+    pub fn evaluate(&self, active_scopes: &HashSet<Scope>, context_keys: &HashSet<String>) -> bool {
+        self.0.evaluate(&|name| {
+            is_scope_active(&Scope::from(name), active_scopes) || context_keys.contains(name)
+        })
+    }
+}
+
+impl Expr {
+    fn evaluate(&self, is_set: &dyn Fn(&str) -> bool) -> bool {
+        match self {
+            Self::Name(name) => is_set(name),
+            Self::Not(expr) => !expr.evaluate(is_set),
+            Self::And(left, right) => left.evaluate(is_set) && right.evaluate(is_set),
+            Self::Or(left, right) => left.evaluate(is_set) || right.evaluate(is_set),
+        }
+    }
+
+    /// Writes the expression, with parentheses only around `||` inside `&&`
+    /// and around binary expressions inside `!`.
+    fn write(&self, f: &mut Formatter<'_>, parent: u8) -> std::fmt::Result {
+        let precedence = match self {
+            Self::Or(..) => 0,
+            Self::And(..) => 1,
+            Self::Not(_) | Self::Name(_) => 2,
+        };
+        if precedence < parent {
+            write!(f, "(")?;
+        }
+        match self {
+            Self::Name(name) => write!(f, "{}", name)?,
+            Self::Not(expr) => {
+                write!(f, "!")?;
+                expr.write(f, 2)?;
+            }
+            Self::And(left, right) => {
+                left.write(f, 1)?;
+                write!(f, " && ")?;
+                right.write(f, 1)?;
+            }
+            Self::Or(left, right) => {
+                left.write(f, 0)?;
+                write!(f, " || ")?;
+                right.write(f, 0)?;
+            }
+        }
+        if precedence < parent {
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for WhenClause {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.0.write(f, 0)
+    }
+}
+
+impl FromStr for WhenClause {
+    type Err = WhenParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser { input, pos: 0 };
+        let expr = parser.or()?;
+        parser.skip_whitespace();
+        if parser.pos < input.len() {
+            return Err(parser.error("unexpected input", parser.pos..input.len()));
+        }
+        Ok(WhenClause(expr))
+    }
+}
+
+/// Recursive descent parser of when clauses.
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn or(&mut self) -> Result<Expr, WhenParseError> {
+        let mut expr = self.and()?;
+        while self.eat("||") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, WhenParseError> {
+        let mut expr = self.unary()?;
+        while self.eat("&&") {
+            expr = Expr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr, WhenParseError> {
+        if self.eat("!") {
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+
+        if self.eat("(") {
+            let start = self.pos - 1;
+            let expr = self.or()?;
+            if !self.eat(")") {
+                return Err(self.error("unclosed parenthesis", start..start + 1));
+            }
+            return Ok(expr);
+        }
+
+        self.skip_whitespace();
+        let rest = &self.input[self.pos..];
+        let len = rest.find(|c: char| !is_name_char(c)).unwrap_or(rest.len());
+        if len == 0 {
+            let end = rest.chars().next().map_or(0, char::len_utf8);
+            return Err(self.error("expected a name", self.pos..self.pos + end));
+        }
+        self.pos += len;
+        Ok(Expr::Name(rest[..len].to_string()))
+    }
+
+    /// Consumes `token` after optional whitespace if it comes next.
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        if self.input[self.pos..].starts_with(token) {
+            self.pos += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.input[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn error(&self, message: &str, span: Range<usize>) -> WhenParseError {
+        WhenParseError {
+            message: message.to_string(),
+            span,
+        }
+    }
+}
+
+/// Characters of scope and context key names, which include the `*` scope.
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ':' | '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scopes(scopes: &[&str]) -> HashSet<Scope> {
+        scopes.iter().map(|scope| Scope::from(*scope)).collect()
+    }
+
+    fn names(names: &[&str]) -> HashSet<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_and_evaluate() {
+        let when: WhenClause = "editor && !modalOpen || focusInSearch".parse().unwrap();
+        assert_eq!(when.to_string(), "editor && !modalOpen || focusInSearch");
+
+        assert!(when.evaluate(&scopes(&["editor"]), &names(&[])));
+        assert!(!when.evaluate(&scopes(&["editor"]), &names(&["modalOpen"])));
+        assert!(when.evaluate(&scopes(&[]), &names(&["modalOpen", "focusInSearch"])));
+        assert!(!when.evaluate(&scopes(&["*"]), &names(&[])));
+
+        let when: WhenClause = "!(a || b) && (c || *)".parse().unwrap();
+        assert_eq!(when.to_string(), "!(a || b) && (c || *)");
+        assert!(when.evaluate(&scopes(&["*"]), &names(&[])));
+        assert!(!when.evaluate(&scopes(&["*", "b"]), &names(&[])));
+
+        let when: WhenClause = "editor.* && !editor.vim".parse().unwrap();
+        assert!(when.evaluate(&scopes(&["editor.emacs"]), &names(&[])));
+        assert!(!when.evaluate(&scopes(&["editor.vim.insert"]), &names(&[])));
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error = |input: &str| input.parse::<WhenClause>().unwrap_err().span();
+        assert_eq!(error(""), 0..0);
+        assert_eq!(error("editor &&"), 9..9);
+        assert_eq!(error("editor & modal"), 7..14);
+        assert_eq!(error("(editor || modal"), 0..1);
+        assert_eq!(error("editor || ?"), 10..11);
+    }
+}
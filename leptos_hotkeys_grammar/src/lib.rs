@@ -0,0 +1,511 @@
+//! Grammar of key combination strings like `"ctrl+shift+k"`, shared by
+//! [`leptos_hotkeys`](https://docs.rs/leptos_hotkeys) and the procedural macros
+//! validating literals at compile time, so it only depends on `std`.
+//!
+//! Its types are re-exported by `leptos_hotkeys`, use them from there.
+
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+
+/// Error returned when a key combination string can't be parsed.
+///
+/// Every variant carries the byte span of the offending part of the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HotkeyParseError {
+    /// A `+`-separated segment is empty, as in `"ctrl++s"` or `""`.
+    EmptySegment { span: Range<usize> },
+    /// A segment is neither a modifier nor a known key name, as in `"ctlr+s"`.
+    UnknownKey { key: String, span: Range<usize> },
+    /// The same modifier appears twice in a chord, as in `"ctrl+control+s"`.
+    DuplicateModifier {
+        modifier: String,
+        span: Range<usize>,
+    },
+    /// The chord only contains modifiers, so it can never be triggered.
+    ModifierOnly { span: Range<usize> },
+    /// A single chord is expected, but the input is a list or a sequence, as
+    /// in `"ctrl+k,ctrl+p"` or `"g i"`.
+    NotAChord { span: Range<usize> },
+}
+
+impl HotkeyParseError {
+    /// Byte range of the input that caused the error.
+    pub fn span(&self) -> Range<usize> {
+        match self {
+            Self::EmptySegment { span }
+            | Self::UnknownKey { span, .. }
+            | Self::DuplicateModifier { span, .. }
+            | Self::ModifierOnly { span }
+            | Self::NotAChord { span } => span.clone(),
+        }
+    }
+
+    /// Shifts the span of the error by `by` bytes.
+    fn offset(mut self, by: usize) -> Self {
+        match &mut self {
+            Self::EmptySegment { span }
+            | Self::UnknownKey { span, .. }
+            | Self::DuplicateModifier { span, .. }
+            | Self::ModifierOnly { span }
+            | Self::NotAChord { span } => *span = span.start + by..span.end + by,
+        }
+        self
+    }
+}
+
+impl Display for HotkeyParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let span = self.span();
+        match self {
+            Self::EmptySegment { .. } => write!(f, "empty key at {}..{}", span.start, span.end),
+            Self::UnknownKey { key, .. } => {
+                write!(f, "unknown key {:?} at {}..{}", key, span.start, span.end)
+            }
+            Self::DuplicateModifier { modifier, .. } => write!(
+                f,
+                "duplicate modifier {:?} at {}..{}",
+                modifier, span.start, span.end
+            ),
+            Self::ModifierOnly { .. } => write!(
+                f,
+                "key combination at {}..{} only contains modifiers",
+                span.start, span.end
+            ),
+            Self::NotAChord { .. } => write!(
+                f,
+                "key combination at {}..{} is not a single chord",
+                span.start, span.end
+            ),
+        }
+    }
+}
+
+impl std::error::Error for HotkeyParseError {}
+
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Modifier {
+    Alt,
+    Ctrl,
+    Meta,
+    Shift,
+    /// Meta on Apple platforms and Ctrl elsewhere.
+    Primary,
+    /// Modifier states read with `KeyboardEvent.getModifierState`. They can be
+    /// negated with `!`, as in `!capslock`.
+    AltGraph,
+    CapsLock,
+    Fn,
+    NumLock,
+}
+
+impl Modifier {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "controlleft" | "controlright" | "ctrl" | "control" => Some(Self::Ctrl),
+
+            "alt" | "altleft" | "altright" => Some(Self::Alt),
+            "option" => Some(Self::Alt), // macos variant
+
+            "metaleft" | "metaright" | "meta" => Some(Self::Meta),
+            "command" | "cmd" => Some(Self::Meta), // macos variant
+            "super" => Some(Self::Meta),           // linux variant
+            "win" => Some(Self::Meta),             // windows variant
+
+            "shiftleft" | "shiftright" | "shift" => Some(Self::Shift),
+
+            "mod" | "primary" => Some(Self::Primary),
+
+            "altgraph" | "altgr" => Some(Self::AltGraph),
+            "capslock" => Some(Self::CapsLock),
+            "fn" => Some(Self::Fn),
+            "numlock" => Some(Self::NumLock),
+
+            _ => None,
+        }
+    }
+
+    /// Name of the key toggling the state, for a chord pressing the key itself
+    /// like `"capslock"`.
+    fn key_name(self) -> Option<&'static str> {
+        match self {
+            Self::CapsLock => Some("capslock"),
+            Self::Fn => Some("fn"),
+            Self::NumLock => Some("numlock"),
+            _ => None,
+        }
+    }
+
+    /// Whether the modifier is a state rather than a held key.
+    pub(crate) fn is_state(self) -> bool {
+        matches!(
+            self,
+            Self::AltGraph | Self::CapsLock | Self::Fn | Self::NumLock
+        )
+    }
+}
+
+/// Side of the keyboard a modifier is pressed on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ModifierSide {
+    /// Either side, as in `ctrl`.
+    #[default]
+    Any,
+    /// The left key, as in `controlleft`.
+    Left,
+    /// The right key, as in `controlright`.
+    Right,
+}
+
+impl ModifierSide {
+    pub fn from_name(name: &str) -> Self {
+        if name.ends_with("left") {
+            Self::Left
+        } else if name.ends_with("right") {
+            Self::Right
+        } else {
+            Self::Any
+        }
+    }
+}
+
+/// Value of a `KeyboardEvent` that key names are compared with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyMode {
+    /// The character or name of the key, like `"?"`, which depends on the
+    /// keyboard layout. Selected per key with a `key:` prefix.
+    Key,
+    /// The physical key, like `"Slash"`, whatever the layout. Selected per key
+    /// with a `code:` prefix.
+    Code,
+}
+
+impl Default for KeyMode {
+    /// [`KeyMode::Key`] with the `use_key` feature, [`KeyMode::Code`] otherwise.
+    fn default() -> Self {
+        if cfg!(feature = "use_key") {
+            Self::Key
+        } else {
+            Self::Code
+        }
+    }
+}
+
+impl KeyMode {
+    /// Splits the `key:` or `code:` prefix off a lowercased key name.
+    pub fn split(name: &str) -> (Option<Self>, &str) {
+        if let Some(name) = name.strip_prefix("key:") {
+            (Some(Self::Key), name)
+        } else if let Some(name) = name.strip_prefix("code:") {
+            (Some(Self::Code), name)
+        } else {
+            (None, name)
+        }
+    }
+
+    fn prefix(self) -> &'static str {
+        match self {
+            Self::Key => "key:",
+            Self::Code => "code:",
+        }
+    }
+}
+
+/// A single chord: modifiers held together with one or more keys.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Chord {
+    pub modifiers: Vec<(Modifier, ModifierSide)>,
+    /// Modifier states that must be active (`true`) or inactive (`false`).
+    pub states: Vec<(Modifier, bool)>,
+    pub keys: Vec<String>,
+}
+
+impl Chord {
+    fn push(&mut self, segment: &str, span: Range<usize>) -> Result<(), HotkeyParseError> {
+        let name = segment.to_lowercase();
+        let negated = name.strip_prefix('!').filter(|name| !name.is_empty());
+        if let Some(modifier) = Modifier::from_name(negated.unwrap_or(&name)) {
+            if negated.is_some() && !modifier.is_state() {
+                return Err(HotkeyParseError::UnknownKey {
+                    key: segment.to_string(),
+                    span,
+                });
+            }
+            if self.modifiers.iter().any(|(other, _)| *other == modifier)
+                || self.states.iter().any(|(other, _)| *other == modifier)
+            {
+                return Err(HotkeyParseError::DuplicateModifier {
+                    modifier: segment.to_string(),
+                    span,
+                });
+            }
+            if modifier.is_state() {
+                self.states.push((modifier, negated.is_none()));
+            } else {
+                self.modifiers
+                    .push((modifier, ModifierSide::from_name(&name)));
+            }
+        } else if let (Some(mode), key) = KeyMode::split(&name) {
+            match key {
+                "spacebar" | "space" => self.keys.push(format!("{}spacebar", mode.prefix())),
+                key if is_known_key(key)
+                    && match mode {
+                        KeyMode::Key => !is_code_only_name(key),
+                        KeyMode::Code => is_code_name(key),
+                    } =>
+                {
+                    self.keys.push(name)
+                }
+                _ => {
+                    return Err(HotkeyParseError::UnknownKey {
+                        key: segment.to_string(),
+                        span,
+                    })
+                }
+            }
+        } else if name == "spacebar" || name == "space" {
+            self.keys.push("spacebar".to_string());
+        } else if is_known_key(&name) {
+            self.keys.push(name);
+        } else {
+            return Err(HotkeyParseError::UnknownKey {
+                key: segment.to_string(),
+                span,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Parses a `,`-separated list of sequences, like `"ctrl+s,ctrl+k ctrl+s"`.
+pub fn parse_list(input: &str) -> Result<Vec<Vec<Chord>>, HotkeyParseError> {
+    input
+        .split(',')
+        .map(|part| parse_sequence(part).map_err(|err| err.offset(offset_in(input, part))))
+        .collect()
+}
+
+/// Parses a sequence of `+`-separated chords like `"ctrl+k ctrl+s"`.
+///
+/// Names are case insensitive and whitespace around `+` is ignored, while
+/// whitespace between two names separates chords. A segment made of a single
+/// space is the spacebar. Key names may be prefixed with `key:` or `code:` to
+/// choose the [`KeyMode`] they are compared with.
+pub fn parse_sequence(input: &str) -> Result<Vec<Chord>, HotkeyParseError> {
+    let mut chords = Vec::new();
+    let mut chord = Chord::default();
+    let mut chord_span = 0..0;
+
+    for segment in input.split('+') {
+        if segment == " " {
+            chord.keys.push("spacebar".to_string());
+            chord_span.end = offset_in(input, segment) + 1;
+            continue;
+        }
+
+        let trimmed = segment.trim();
+        if trimmed.is_empty() {
+            let start = offset_in(input, trimmed);
+            return Err(HotkeyParseError::EmptySegment { span: start..start });
+        }
+
+        for (i, name) in trimmed.split_whitespace().enumerate() {
+            let start = offset_in(input, name);
+            let span = start..start + name.len();
+
+            if i > 0 {
+                chords.push(finish_chord(chord, chord_span)?);
+                chord = Chord::default();
+                chord_span = span.clone();
+            }
+
+            chord.push(name, span.clone())?;
+            chord_span.end = span.end;
+        }
+    }
+
+    chords.push(finish_chord(chord, chord_span)?);
+    Ok(chords)
+}
+
+fn finish_chord(mut chord: Chord, span: Range<usize>) -> Result<Chord, HotkeyParseError> {
+    // a lock key without another key, as in `"capslock"` or `"ctrl+numlock"`,
+    // is the key itself rather than its state
+    if chord.keys.is_empty() {
+        let lock_key = chord
+            .states
+            .iter()
+            .rposition(|(modifier, active)| *active && modifier.key_name().is_some());
+        if let Some(i) = lock_key {
+            let (modifier, _) = chord.states.remove(i);
+            chord.keys.extend(modifier.key_name().map(str::to_string));
+        }
+    }
+    if chord.keys.is_empty() {
+        return Err(HotkeyParseError::ModifierOnly { span });
+    }
+    Ok(chord)
+}
+
+/// Byte offset of `part` in `input`, `part` being a subslice of `input`.
+fn offset_in(input: &str, part: &str) -> usize {
+    part.as_ptr() as usize - input.as_ptr() as usize
+}
+
+/// Whether `key` (lowercased) can be a `KeyboardEvent` `code` value, which is
+/// an alphanumeric name like `keyw` or `slash` rather than a character.
+fn is_code_name(key: &str) -> bool {
+    key.len() > 1 && key.bytes().all(|byte| byte.is_ascii_alphanumeric())
+}
+
+/// Whether `key` (lowercased) is only ever a `KeyboardEvent` `code` value, like
+/// `keyw`, `digit1` or `slash`, so it can never match under `key:`.
+fn is_code_only_name(key: &str) -> bool {
+    let single = |rest: &str| rest.chars().count() == 1;
+    key.strip_prefix("key").is_some_and(single)
+        || key.strip_prefix("digit").is_some_and(single)
+        || key.starts_with("numpad")
+        || CODE_ONLY_KEYS.contains(&key)
+}
+
+/// Whether `key` (lowercased) is a known `KeyboardEvent` `code` or `key` value.
+///
+/// Any single character is accepted, since that's what `key` reports for
+/// printable characters.
+pub(crate) fn is_known_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    if chars.next().is_some() && chars.next().is_none() {
+        return true;
+    }
+
+    if let Some(n) = key.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+        return (1..=24).contains(&n);
+    }
+
+    for prefix in ["key", "digit", "numpad"] {
+        if let Some(rest) = key.strip_prefix(prefix) {
+            let mut chars = rest.chars();
+            if let (Some(c), None) = (chars.next(), chars.next()) {
+                match prefix {
+                    "key" => return c.is_ascii_lowercase(),
+                    _ => return c.is_ascii_digit(),
+                }
+            }
+        }
+    }
+
+    NAMED_KEYS.contains(&key) || CODE_ONLY_KEYS.contains(&key)
+}
+
+/// Lowercased `code` values that `key` never reports, and that aren't covered
+/// by the patterns in [`is_known_key`].
+const CODE_ONLY_KEYS: &[&str] = &[
+    // punctuation
+    "backquote",
+    "backslash",
+    "bracketleft",
+    "bracketright",
+    "comma",
+    "equal",
+    "intlbackslash",
+    "intlro",
+    "intlyen",
+    "minus",
+    "period",
+    "quote",
+    "semicolon",
+    "slash",
+    // launchers
+    "launchapp1",
+    "launchapp2",
+    "mediaselect",
+];
+
+/// Lowercased `code` and `key` values that aren't covered by the patterns in
+/// [`is_known_key`].
+const NAMED_KEYS: &[&str] = &[
+    // whitespace and editing
+    "backspace",
+    "delete",
+    "enter",
+    "escape",
+    "insert",
+    "tab",
+    // navigation
+    "arrowdown",
+    "arrowleft",
+    "arrowright",
+    "arrowup",
+    "end",
+    "home",
+    "pagedown",
+    "pageup",
+    // locks and system
+    "capslock",
+    "contextmenu",
+    "fn",
+    "fnlock",
+    "numlock",
+    "pause",
+    "printscreen",
+    "scrolllock",
+    "os",
+    "power",
+    "sleep",
+    "wakeup",
+    "eject",
+    "help",
+    "dead",
+    "unidentified",
+    // numpad
+    "numpadadd",
+    "numpadbackspace",
+    "numpadclear",
+    "numpadcomma",
+    "numpaddecimal",
+    "numpaddivide",
+    "numpadenter",
+    "numpadequal",
+    "numpadmultiply",
+    "numpadparenleft",
+    "numpadparenright",
+    "numpadsubtract",
+    "clear",
+    // international input
+    "convert",
+    "kanamode",
+    "lang1",
+    "lang2",
+    "lang3",
+    "lang4",
+    "lang5",
+    "nonconvert",
+    // media and browser
+    "audiovolumedown",
+    "audiovolumemute",
+    "audiovolumeup",
+    "browserback",
+    "browserfavorites",
+    "browserforward",
+    "browserhome",
+    "browserrefresh",
+    "browsersearch",
+    "browserstop",
+    "launchmail",
+    "mediaplaypause",
+    "mediastop",
+    "mediatracknext",
+    "mediatrackprevious",
+    "mediaplay",
+    "mediapause",
+    "copy",
+    "cut",
+    "paste",
+    "undo",
+    "redo",
+    "find",
+    "open",
+    "props",
+    "select",
+    "again",
+];
@@ -0,0 +1,239 @@
+//! Procedural macros of [`leptos_hotkeys`](https://docs.rs/leptos_hotkeys).
+//!
+//! They are not meant to be used directly, but through the `use_hotkeys!` and
+//! `use_hotkeys_ref!` macros, which pass the path of the `leptos_hotkeys` crate,
+//! and the `HotkeyScope` derive re-exported by `leptos_hotkeys`.
+
+use leptos_hotkeys_grammar::{parse_list, Chord, HotkeyParseError, Modifier, ModifierSide};
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2, TokenTree};
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, LitStr};
+
+/// Parses a literal key combination like `"ctrl+s,meta+s"` at compile time.
+///
+/// Takes the path of the `leptos_hotkeys` crate, a comma and the literal, and
+/// expands to a `HashSet<Hotkey>` built from constants. Invalid key
+/// combinations are reported with `compile_error!`.
+#[proc_macro]
+pub fn hotkeys(input: TokenStream) -> TokenStream {
+    let mut tokens = TokenStream2::from(input).into_iter();
+    let krate = tokens
+        .by_ref()
+        .take_while(|token| !matches!(token, TokenTree::Punct(punct) if punct.as_char() == ','))
+        .collect::<TokenStream2>();
+
+    let literal = match syn::parse2::<LitStr>(tokens.collect()) {
+        Ok(literal) => literal,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    let key_combination = literal.value();
+    match parse_list(&key_combination) {
+        Ok(sequences) => expand(&krate, &sequences).into(),
+        Err(err) => syn::Error::new(
+            error_span(&literal, &err),
+            error_message(&key_combination, &err),
+        )
+        .to_compile_error()
+        .into(),
+    }
+}
+
+fn expand(krate: &TokenStream2, sequences: &[Vec<Chord>]) -> TokenStream2 {
+    let sequences = sequences.iter().map(|chords| {
+        let chords = chords.iter().map(|chord| {
+            let modifiers = chord.modifiers.iter().map(|(modifier, side)| {
+                let modifier = modifier_tokens(krate, *modifier);
+                let side = match side {
+                    ModifierSide::Any => quote!(#krate::ModifierSide::Any),
+                    ModifierSide::Left => quote!(#krate::ModifierSide::Left),
+                    ModifierSide::Right => quote!(#krate::ModifierSide::Right),
+                };
+                quote!((#modifier, #side))
+            });
+            let states = chord.states.iter().map(|(modifier, active)| {
+                let modifier = modifier_tokens(krate, *modifier);
+                quote!((#modifier, #active))
+            });
+            let keys = &chord.keys;
+
+            quote! {
+                #krate::__private::StaticChord {
+                    modifiers: &[#(#modifiers),*],
+                    states: &[#(#states),*],
+                    keys: &[#(#keys),*],
+                }
+            }
+        });
+
+        quote!(&[#(#chords),*])
+    });
+
+    quote! {
+        {
+            const HOTKEYS: &[&[#krate::__private::StaticChord]] = &[#(#sequences),*];
+            #krate::__private::hotkeys_from_static(HOTKEYS)
+        }
+    }
+}
+
+/// Implements `HotkeyScope` for an enum of unit variants, and `From` it for
+/// `Scope`.
+///
+/// Variants are named in snake case, `SearchBar` being `"search_bar"` and
+/// `HTTPServer` being `"http_server"`, unless renamed with `#[scope = "editor.vim"]`.
+///
+/// The impls refer to `::leptos_hotkeys`, which can be changed with
+/// `#[scope(crate = "path::to::leptos_hotkeys")]` on the enum, for a crate
+/// re-exporting it under another name.
+#[proc_macro_derive(HotkeyScope, attributes(scope))]
+pub fn derive_hotkey_scope(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    match expand_hotkey_scope(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn expand_hotkey_scope(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Enum(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            input,
+            "HotkeyScope can only be derived for enums",
+        ));
+    };
+
+    let mut krate = quote!(::leptos_hotkeys);
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("scope"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("crate") {
+                let path = meta.value()?.parse::<LitStr>()?.parse::<syn::Path>()?;
+                krate = quote!(#path);
+                Ok(())
+            } else {
+                Err(meta.error("expected `crate = \"...\"`"))
+            }
+        })?;
+    }
+
+    let arms = data
+        .variants
+        .iter()
+        .map(|variant| {
+            if !matches!(variant.fields, Fields::Unit) {
+                return Err(syn::Error::new_spanned(
+                    &variant.fields,
+                    "HotkeyScope variants can't have fields",
+                ));
+            }
+
+            let mut name = snake_case(&variant.ident.to_string());
+            for attr in variant
+                .attrs
+                .iter()
+                .filter(|attr| attr.path().is_ident("scope"))
+            {
+                let value = &attr.meta.require_name_value()?.value;
+                let literal = syn::parse2::<LitStr>(quote!(#value))?;
+                name = literal.value();
+                if name.chars().any(char::is_uppercase) {
+                    return Err(syn::Error::new_spanned(
+                        literal,
+                        "scope names must be lowercase",
+                    ));
+                }
+            }
+
+            let ident = &variant.ident;
+            Ok(quote!(Self::#ident => #name))
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #krate::HotkeyScope for #ident #ty_generics #where_clause {
+            fn into_scope(self) -> #krate::Scope {
+                #krate::Scope::from_static(match self {
+                    #(#arms,)*
+                })
+            }
+        }
+
+        impl #impl_generics ::core::convert::From<#ident #ty_generics> for #krate::Scope #where_clause {
+            fn from(scope: #ident #ty_generics) -> Self {
+                #krate::HotkeyScope::into_scope(scope)
+            }
+        }
+    })
+}
+
+/// `SearchBar` to `search_bar`, keeping runs of capitals together as one
+/// word: `HTTPServer` to `http_server`.
+fn snake_case(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut snake = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() {
+            let after_word = i > 0 && !chars[i - 1].is_uppercase();
+            let starts_word = i > 0
+                && chars[i - 1].is_uppercase()
+                && chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+            if after_word || starts_word {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
+fn modifier_tokens(krate: &TokenStream2, modifier: Modifier) -> TokenStream2 {
+    match modifier {
+        Modifier::Alt => quote!(#krate::__private::Modifier::Alt),
+        Modifier::Ctrl => quote!(#krate::__private::Modifier::Ctrl),
+        Modifier::Meta => quote!(#krate::__private::Modifier::Meta),
+        Modifier::Shift => quote!(#krate::__private::Modifier::Shift),
+        Modifier::Primary => quote!(#krate::__private::Modifier::Primary),
+        Modifier::AltGraph => quote!(#krate::__private::Modifier::AltGraph),
+        Modifier::CapsLock => quote!(#krate::__private::Modifier::CapsLock),
+        Modifier::Fn => quote!(#krate::__private::Modifier::Fn),
+        Modifier::NumLock => quote!(#krate::__private::Modifier::NumLock),
+    }
+}
+
+/// Span of the invalid segment when the compiler can point inside the literal,
+/// or of the whole literal otherwise.
+fn error_span(literal: &LitStr, err: &HotkeyParseError) -> Span {
+    let token = literal.token();
+    let span = err.span();
+
+    // byte offsets only map to the source for plain strings without escapes
+    if token.to_string() == format!("\"{}\"", literal.value()) {
+        if let Some(subspan) = token.subspan(span.start + 1..span.end + 1) {
+            return subspan;
+        }
+    }
+    literal.span()
+}
+
+fn error_message(key_combination: &str, err: &HotkeyParseError) -> String {
+    let span = err.span();
+    let padding = key_combination[..span.start].chars().count();
+    let width = key_combination[span].chars().count().max(1);
+
+    format!(
+        "invalid hotkey: {}\n  {}\n  {}{}",
+        err,
+        key_combination,
+        " ".repeat(padding),
+        "^".repeat(width)
+    )
+}